@@ -0,0 +1,19 @@
+// Copyright (C) 2019-2021 Crust Network Technologies Ltd.
+// This file is part of Crust.
+
+//! Runtime API definition for the market module.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use primitives::MerkleRoot;
+use sp_std::vec::Vec;
+
+sp_api::decl_runtime_apis! {
+    pub trait MarketApi<FileInfo> where
+        FileInfo: Codec,
+    {
+        /// The files whose size is within `[min, max]`, paginated by `start` and `limit`.
+        fn files_by_size(min: u64, max: u64, start: u32, limit: u32) -> Vec<(MerkleRoot, FileInfo)>;
+    }
+}
@@ -0,0 +1,80 @@
+// Copyright (C) 2019-2021 Crust Network Technologies Ltd.
+// This file is part of Crust.
+
+//! RPC interface for the market module.
+
+use std::sync::Arc;
+
+use codec::Codec;
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use serde::Serialize;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::Bytes;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+
+pub use market_rpc_runtime_api::MarketApi as MarketRuntimeApi;
+
+#[rpc]
+pub trait MarketApi<BlockHash, FileInfo> {
+    /// Get the files whose size is within `[min, max]`, paginated by `start` and `limit`.
+    #[rpc(name = "market_filesBySize")]
+    fn files_by_size(
+        &self,
+        min: u64,
+        max: u64,
+        start: u32,
+        limit: u32,
+        at: Option<BlockHash>
+    ) -> Result<Vec<(Bytes, FileInfo)>>;
+}
+
+/// A struct that implements the [`MarketApi`].
+pub struct Market<C, Block, FileInfo> {
+    client: Arc<C>,
+    _marker: std::marker::PhantomData<(Block, FileInfo)>,
+}
+
+impl<C, Block, FileInfo> Market<C, Block, FileInfo> {
+    /// Create new `Market` with the given reference to the client.
+    pub fn new(client: Arc<C>) -> Self {
+        Market { client, _marker: Default::default() }
+    }
+}
+
+/// Error code of the runtime api call.
+const RUNTIME_ERROR: i64 = 1;
+
+fn runtime_error(e: impl std::fmt::Debug) -> RpcError {
+    RpcError {
+        code: ErrorCode::ServerError(RUNTIME_ERROR),
+        message: "Unable to query market info.".into(),
+        data: Some(format!("{:?}", e).into()),
+    }
+}
+
+impl<C, Block, FileInfo> MarketApi<<Block as BlockT>::Hash, FileInfo>
+    for Market<C, Block, FileInfo>
+where
+    Block: BlockT,
+    C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+    C::Api: MarketRuntimeApi<Block, FileInfo>,
+    FileInfo: Codec + Serialize + Send + Sync + 'static,
+{
+    fn files_by_size(
+        &self,
+        min: u64,
+        max: u64,
+        start: u32,
+        limit: u32,
+        at: Option<<Block as BlockT>::Hash>
+    ) -> Result<Vec<(Bytes, FileInfo)>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+        api.files_by_size(&at, min, max, start, limit)
+            .map(|files| files.into_iter().map(|(cid, file_info)| (cid.into(), file_info)).collect())
+            .map_err(runtime_error)
+    }
+}
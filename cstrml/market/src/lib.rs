@@ -37,7 +37,7 @@ pub mod benchmarking;
 
 use primitives::{
     constants::market::*, traits::{
-        BenefitInterface, MarketInterface, SworkerInterface, UsableCurrency
+        BenefitInterface, MarketInterface, MinReplication, OnFileClosed, SworkerInterface, UsableCurrency
     }, BlockNumber, MerkleRoot, ReportSlot, SworkerAnchor
 };
 
@@ -232,6 +232,12 @@ pub trait Config: system::Config {
     /// Maximum file size
     type MaximumFileSize: Get<u64>;
 
+    /// Handler for the expired files being closed
+    type OnFileClosed: OnFileClosed;
+
+    /// The minimum replicas for a file to be durable
+    type MinReplication: MinReplication;
+
     /// Weight information for extrinsics in this pallet.
     type WeightInfo: WeightInfo;
 }
@@ -398,29 +404,16 @@ decl_module! {
             _memo: Vec<u8>
         ) -> DispatchResult {
             // 1. Service should be available right now.
+            // No `OrderFailed` here, the order isn't rejected but the market is closed for everyone
             ensure!(Self::enable_market(), Error::<T>::PlaceOrderNotAvailable);
             let who = ensure_signed(origin)?;
 
-            // 2. Calculate amount.
-            let mut charged_file_size = reported_file_size;
-            if let Some(file_info) = Self::filesv2(&cid) {
-                if file_info.file_size <= reported_file_size {
-                    // Charge user with real file size
-                    charged_file_size = file_info.file_size;
-                } else {
-                    Err(Error::<T>::FileSizeNotCorrect)?
-                }
-            }
-            // 3. charged_file_size should be smaller than 32G
-            ensure!(charged_file_size < T::MaximumFileSize::get(), Error::<T>::FileTooLarge);
-
-            let (file_base_fee, amount) = Self::get_file_fee(charged_file_size);
-
-            // 4. Check client can afford the sorder
-            ensure!(T::Currency::usable_balance(&who) >= file_base_fee + amount + tips, Error::<T>::InsufficientCurrency);
-
-            // 5. Split into reserved, storage and staking account
-            let amount = Self::split_into_reserved_and_storage_and_staking_pot(&who, amount.clone(), file_base_fee, tips, AllowDeath)?;
+            // 2-5. Charge the client, emit the failure event if it's rejected
+            let (charged_file_size, amount) = Self::charge_storage_order(&who, &cid, reported_file_size, tips)
+                .map_err(|e| {
+                    Self::deposit_event(RawEvent::OrderFailed(who.clone(), cid.clone()));
+                    e
+                })?;
 
             let curr_bn = Self::get_current_block_number();
 
@@ -949,6 +942,7 @@ impl<T: Config> Module<T> {
                 <FilesV2<T>>::remove(&cid);
                 FileKeysCount::mutate(|count| *count = count.saturating_sub(1));
                 Self::deposit_event(RawEvent::FileClosed(cid.clone()));
+                T::OnFileClosed::on_file_closed(&cid, file_info.file_size);
             };
         }
         Ok(())
@@ -1171,6 +1165,38 @@ impl<T: Config> Module<T> {
         }
     }
 
+    /// Check the storage order and charge the client, return the charged file size and
+    /// the amount left for the storage pot.
+    fn charge_storage_order(
+        who: &T::AccountId,
+        cid: &MerkleRoot,
+        reported_file_size: u64,
+        tips: BalanceOf<T>
+    ) -> Result<(u64, BalanceOf<T>), DispatchError> {
+        // 2. Calculate amount.
+        let mut charged_file_size = reported_file_size;
+        if let Some(file_info) = Self::filesv2(cid) {
+            if file_info.file_size <= reported_file_size {
+                // Charge user with real file size
+                charged_file_size = file_info.file_size;
+            } else {
+                Err(Error::<T>::FileSizeNotCorrect)?
+            }
+        }
+        // 3. charged_file_size should be smaller than 32G
+        ensure!(charged_file_size < T::MaximumFileSize::get(), Error::<T>::FileTooLarge);
+
+        let (file_base_fee, amount) = Self::get_file_fee(charged_file_size);
+
+        // 4. Check client can afford the sorder
+        ensure!(T::Currency::usable_balance(who) >= file_base_fee + amount + tips, Error::<T>::InsufficientCurrency);
+
+        // 5. Split into reserved, storage and staking account
+        let amount = Self::split_into_reserved_and_storage_and_staking_pot(who, amount, file_base_fee, tips, AllowDeath)?;
+
+        Ok((charged_file_size, amount))
+    }
+
     // Split total value into three pot and return the amount in storage pot
     // Currently
     // 10% into reserved pot
@@ -1286,6 +1312,26 @@ impl<T: Config> Module<T> {
 
         file_size + file_size * ((alpha * multiplier as f64) as u64) / multiplier
     }
+
+    /// Get the files whose size is within `[min, max]`, skipping the first `start` matches
+    /// and returning at most `limit` of them.
+    ///
+    /// This iterates the whole `FilesV2`, which costs one storage read per file, so it must
+    /// only be called off-chain (e.g. through the runtime api) and callers should paginate.
+    pub fn files_by_size(min: u64, max: u64, start: u32, limit: u32) -> Vec<(MerkleRoot, FileInfoV2<T::AccountId, BalanceOf<T>>)> {
+        <FilesV2<T>>::iter()
+            .filter(|(_, file_info)| file_info.file_size >= min && file_info.file_size <= max)
+            .skip(start as usize)
+            .take(limit as usize)
+            .collect()
+    }
+
+    /// Whether the file has at least the minimum replicas required for its size.
+    pub fn is_file_durable(cid: &MerkleRoot) -> bool {
+        Self::filesv2(cid).map_or(false, |file_info|
+            file_info.reported_replica_count >= T::MinReplication::min_replication(file_info.file_size)
+        )
+    }
 }
 
 decl_event!(
@@ -1331,5 +1377,9 @@ decl_event!(
         /// A file is closed due to expired
         /// The first item is the cid of the file
         FileClosed(MerkleRoot),
+        /// Place a storage order failed.
+        /// The first item is the account who places the storage order.
+        /// The second item is the cid of the file.
+        OrderFailed(AccountId, MerkleRoot),
     }
 );
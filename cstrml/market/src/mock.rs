@@ -17,7 +17,7 @@ use sp_runtime::{
     Perbill,
 };
 use balances::{AccountData, NegativeImbalance};
-pub use primitives::{traits::BenefitInterface, *};
+pub use primitives::{traits::{BenefitInterface, MinReplication, OnFileClosed}, *};
 use swork::{PKInfo, Identity, NegativeImbalanceOf};
 pub use std::{cell::RefCell, collections::HashMap, borrow::Borrow, iter::FromIterator};
 
@@ -45,6 +45,7 @@ thread_local! {
     static LEGAL_CODE: Vec<u8> = hex::decode("781b537d3dcef39dec7b8bce6fdfcd032d8d846640e9b5598b4a9f627188a908").unwrap();
     static MERCHANT_LEDGERS: RefCell<HashMap<AccountId, MockMerchantLedger>> = RefCell::new(Default::default());
     static DISCOUNT_RATIO: RefCell<(u64, u64)> = RefCell::new((0, 0));
+    static CLOSED_FILES: RefCell<Vec<MerkleRoot>> = RefCell::new(vec![]);
 }
 
 pub struct ExistentialDeposit;
@@ -54,6 +55,25 @@ impl Get<u64> for ExistentialDeposit {
     }
 }
 
+pub struct TestFileClosedHandler;
+impl OnFileClosed for TestFileClosedHandler {
+    fn on_file_closed(cid: &MerkleRoot, _: u64) {
+        CLOSED_FILES.with(|v| v.borrow_mut().push(cid.clone()));
+    }
+}
+
+/// Files larger than 100M need 3 replicas, smaller ones need 1.
+pub struct TestMinReplication;
+impl MinReplication for TestMinReplication {
+    fn min_replication(file_size: u64) -> u32 {
+        if file_size > 100_000_000 { 3 } else { 1 }
+    }
+}
+
+pub fn closed_files() -> Vec<MerkleRoot> {
+    CLOSED_FILES.with(|v| v.borrow().clone())
+}
+
 pub struct DiscountRatio;
 impl Get<(u64, u64)> for DiscountRatio {
     fn get() -> (u64, u64) {
@@ -129,7 +149,7 @@ impl system::Config for Test {
     type AccountId = AccountId;
     type Lookup = IdentityLookup<Self::AccountId>;
     type Header = Header;
-    type Event = ();
+    type Event = Event;
     type BlockHashCount = BlockHashCount;
     type DbWeight = RocksDbWeight;
     type Version = ();
@@ -144,7 +164,7 @@ impl system::Config for Test {
 impl balances::Config for Test {
     type Balance = Balance;
     type DustRemoval = ();
-    type Event = ();
+    type Event = Event;
     type ExistentialDeposit = ExistentialDeposit;
     type AccountStore = System;
     type WeightInfo = ();
@@ -189,7 +209,7 @@ parameter_types! {
 
 impl swork::Config for Test {
     type Currency = Balances;
-    type Event = ();
+    type Event = Event;
     type PunishmentSlots = PunishmentSlots;
     type Works = ();
     type MarketInterface = Market;
@@ -220,7 +240,7 @@ impl Config for Test {
     type Currency = balances::Module<Self>;
     type SworkerInterface = Swork;
     type BenefitInterface = TestBenefitInterface;
-    type Event = ();
+    type Event = Event;
     type FileDuration = FileDuration;
     type LiquidityDuration = LiquidityDuration;
     type FileReplica = FileReplica;
@@ -232,6 +252,8 @@ impl Config for Test {
     type StakingRatio = StakingRatio;
     type StorageRatio = StorageRatio;
     type MaximumFileSize = MaximumFileSize;
+    type OnFileClosed = TestFileClosedHandler;
+    type MinReplication = TestMinReplication;
     type WeightInfo = weight::WeightInfo<Test>;
     type RenewRewardRatio = RenewRewardRatio;
 }
@@ -402,6 +424,14 @@ pub fn run_to_block(n: u64) {
     }
 }
 
+pub fn market_events() -> Vec<market::Event<Test>> {
+    System::events()
+        .into_iter()
+        .map(|r| r.event)
+        .filter_map(|e| if let Event::market(inner) = e { Some(inner) } else { None })
+        .collect()
+}
+
 pub fn mock_bond_owner(member: &AccountId, owner: &AccountId) {
     <swork::Identities<Test>>::mutate(member, |maybe_identity| match *maybe_identity {
         Some(ref mut identity) => identity.group = Some(owner.clone()),
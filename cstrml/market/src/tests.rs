@@ -80,15 +80,42 @@ fn place_storage_order_should_fail_due_to_too_large_file_size() {
         mock_bond_owner(&merchant, &merchant);
         add_collateral(&merchant, 60);
 
-        assert_noop!(Market::place_storage_order(
-            Origin::signed(source), cid.clone(),
+        assert_eq!(Market::place_storage_order(
+            Origin::signed(source.clone()), cid.clone(),
             file_size, 0, vec![]
         ),
-        DispatchError::Module {
+        Err(DispatchError::Module {
             index: 3,
             error: 4,
             message: Some("FileTooLarge")
-        });
+        }));
+        assert_eq!(market_events(), vec![RawEvent::OrderFailed(source, cid)]);
+    });
+}
+
+#[test]
+fn place_storage_order_should_emit_failed_event_due_to_insufficient_currency() {
+    new_test_ext().execute_with(|| {
+        // generate 50 blocks first
+        run_to_block(50);
+
+        let source = ALICE;
+        let cid =
+            hex::decode("4e2883ddcbc77cf19979770d756fd332d0c8f815f9de646636169e460e6af6ff").unwrap();
+        let _ = Balances::make_free_balance_be(&source, 100);
+
+        assert_eq!(Market::place_storage_order(
+            Origin::signed(source.clone()), cid.clone(),
+            100, 1000, vec![]
+        ),
+        Err(DispatchError::Module {
+            index: 3,
+            error: 0,
+            message: Some("InsufficientCurrency")
+        }));
+        assert_eq!(market_events(), vec![RawEvent::OrderFailed(source.clone(), cid.clone())]);
+        assert_eq!(Balances::free_balance(&source), 100);
+        assert!(Market::filesv2(&cid).is_none());
     });
 }
 
@@ -311,6 +338,56 @@ fn place_storage_order_should_work_for_extend_scenarios() {
     });
 }
 
+#[test]
+fn is_file_durable_should_work() {
+    new_test_ext().execute_with(|| {
+        // generate 50 blocks first
+        run_to_block(50);
+
+        let source = ALICE;
+        let charlie = CHARLIE;
+        let dave = DAVE;
+        let ferdie = FERDIE;
+        let spower = SPOWER;
+
+        let cid =
+            "QmdwgqZy1MZBfWPi7GcxVsYgJEtmvHg6rsLzbCej3tf3oF".as_bytes().to_vec();
+        // Larger than 100M, 3 replicas are required
+        let file_size = 134289408;
+        let _ = Balances::make_free_balance_be(&source, 20_000_000);
+        for who in vec![charlie.clone(), dave.clone(), ferdie.clone()].iter() {
+            let _ = Balances::make_free_balance_be(&who, 20_000_000);
+            mock_bond_owner(&who, &who);
+            add_collateral(&who, 6_000_000);
+        }
+        assert!(!Market::is_file_durable(&cid));
+
+        assert_ok!(Market::place_storage_order(
+            Origin::signed(source), cid.clone(),
+            file_size, 0, vec![]
+        ));
+        assert!(!Market::is_file_durable(&cid));
+
+        run_to_block(203);
+        let legal_wr_info = legal_work_report_with_added_files();
+        let legal_pk = legal_wr_info.curr_pk.clone();
+        assert_ok!(Market::set_spower_superior(Origin::root(), spower.clone()));
+
+        add_who_into_replica(&cid, file_size, ferdie.clone(), ferdie.clone(), legal_pk.clone(), legal_wr_info.block_number, 203, 203);
+        add_who_into_replica(&cid, file_size, charlie.clone(), charlie.clone(), legal_pk.clone(), legal_wr_info.block_number, 203, 203);
+        assert!(!Market::is_file_durable(&cid));
+
+        add_who_into_replica(&cid, file_size, dave.clone(), dave.clone(), legal_pk.clone(), legal_wr_info.block_number, 203, 203);
+        assert_eq!(Market::filesv2(&cid).unwrap().reported_replica_count, 3);
+        assert!(Market::is_file_durable(&cid));
+
+        // Losing a replica makes it not durable again
+        delete_replica(&cid, file_size, dave.clone(), dave.clone(), legal_pk.clone(), legal_wr_info.block_number, 303, 303);
+        assert_eq!(Market::filesv2(&cid).unwrap().reported_replica_count, 2);
+        assert!(!Market::is_file_durable(&cid));
+    });
+}
+
 #[test]
 fn update_replicas_should_work_for_more_replicas() {
     new_test_ext().execute_with(|| {
@@ -1050,14 +1127,15 @@ fn double_place_storage_order_file_size_check_should_work() {
         );
 
         // 80 < 100 => throw an error
-        assert_noop!(Market::place_storage_order(
+        assert_eq!(Market::place_storage_order(
             Origin::signed(source.clone()), cid1.clone(), 80, 0, vec![]),
-            DispatchError::Module {
+            Err(DispatchError::Module {
                 index: 3,
                 error: 1,
                 message: Some("FileSizeNotCorrect")
-            }
+            })
         );
+        assert_eq!(market_events().last(), Some(&RawEvent::OrderFailed(source.clone(), cid1.clone())));
 
         // 12000000 > 100. Only need amount for 100
         assert_ok!(Market::place_storage_order(
@@ -1600,6 +1678,42 @@ fn calculate_spower_should_work() {
     });
 }
 
+#[test]
+fn files_by_size_should_work() {
+    new_test_ext().execute_with(|| {
+        // generate 50 blocks first
+        run_to_block(50);
+
+        let source = ALICE;
+        let _ = Balances::make_free_balance_be(&source, 20_000_000);
+
+        let sizes = vec![100, 200, 300, 400, 500];
+        for (idx, file_size) in sizes.iter().enumerate() {
+            let cid = format!("QmdwgqZy1MZBfWPi7GcxVsYgJEtmvHg6rsLzbCej3tf3o{}", idx).as_bytes().to_vec();
+            assert_ok!(Market::place_storage_order(
+                Origin::signed(source.clone()), cid,
+                *file_size, 0, vec![]
+            ));
+        }
+
+        let mut found: Vec<u64> = Market::files_by_size(200, 400, 0, 10)
+            .into_iter()
+            .map(|(_, file_info)| file_info.file_size)
+            .collect();
+        found.sort();
+        assert_eq!(found, vec![200, 300, 400]);
+
+        // Pagination
+        let first_page = Market::files_by_size(200, 400, 0, 2);
+        let second_page = Market::files_by_size(200, 400, 2, 2);
+        assert_eq!(first_page.len(), 2);
+        assert_eq!(second_page.len(), 1);
+        assert!(!first_page.contains(&second_page[0]));
+
+        assert!(Market::files_by_size(600, 1000, 0, 10).is_empty());
+    });
+}
+
 #[test]
 fn delete_spower_should_work() {
     new_test_ext().execute_with(|| {
@@ -1809,6 +1923,57 @@ fn reward_liquidator_should_work() {
     });
 }
 
+#[test]
+fn on_file_closed_should_be_called_once() {
+    new_test_ext().execute_with(|| {
+        // generate 50 blocks first
+        run_to_block(50);
+
+        let cid =
+            "QmdwgqZy1MZBfWPi7GcxVsYgJEtmvHg6rsLzbCej3tf3oF".as_bytes().to_vec();
+        let file_size = 134289408; // should less than merchant
+        let source = ALICE;
+        let merchant = BOB;
+        let charlie = CHARLIE;
+        let spower = SPOWER;
+
+        let storage_pot = Market::storage_pot();
+        let _ = Balances::make_free_balance_be(&storage_pot, 1);
+        let _ = Balances::make_free_balance_be(&source, 20_000_000);
+        let _ = Balances::make_free_balance_be(&merchant, 20_000_000);
+        mock_bond_owner(&merchant, &merchant);
+        add_collateral(&merchant, 6_000_000);
+
+        assert_ok!(Market::set_spower_superior(Origin::root(), spower.clone()));
+        assert_ok!(Market::place_storage_order(
+            Origin::signed(source.clone()), cid.clone(),
+            file_size, 0, vec![]
+        ));
+
+        run_to_block(303);
+
+        let legal_wr_info = legal_work_report_with_added_files();
+        let legal_pk = legal_wr_info.curr_pk.clone();
+        register(&legal_pk, LegalCode::get());
+        add_who_into_replica(&cid, file_size, merchant.clone(), merchant.clone(), legal_pk.clone(), legal_wr_info.block_number, 303, 303);
+
+        // The file is still ongoing
+        run_to_block(1000);
+        assert_ok!(Market::calculate_reward(Origin::signed(charlie.clone()), cid.clone()));
+        assert_eq!(Market::filesv2(&cid).is_some(), true);
+        assert!(closed_files().is_empty());
+
+        run_to_block(2503);
+        assert_ok!(Market::calculate_reward(Origin::signed(charlie.clone()), cid.clone()));
+        assert_eq!(Market::filesv2(&cid).is_none(), true);
+        assert_eq!(closed_files(), vec![cid.clone()]);
+
+        // The closed file is gone, the handler won't be called again
+        assert_ok!(Market::calculate_reward(Origin::signed(charlie.clone()), cid.clone()));
+        assert_eq!(closed_files(), vec![cid.clone()]);
+    });
+}
+
 #[test]
 fn reward_merchant_should_work() {
     new_test_ext().execute_with(|| {
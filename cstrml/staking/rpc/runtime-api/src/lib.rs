@@ -0,0 +1,111 @@
+// Copyright (C) 2019-2021 Crust Network Technologies Ltd.
+// This file is part of Crust.
+
+//! Runtime API definition for the staking module.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Codec, Decode, Encode};
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+use sp_runtime::{Perbill, RuntimeDebug};
+use sp_std::vec::Vec;
+
+/// The era index type, same as the one used in the staking module.
+pub type EraIndex = u32;
+
+/// Claim status of an era's rewards.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum EraPayoutStatus {
+    /// The era has not ended yet, so its payout is not computed.
+    NotComputed,
+    /// The payout is computed and some validators haven't claimed it yet.
+    Claimable,
+    /// All validators have claimed their rewards of the era.
+    FullyClaimed,
+    /// The era is out of `HistoryDepth`, its rewards can not be claimed anymore.
+    Pruned,
+}
+
+/// An elected validator with its stake and preference.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct ValidatorInfo<AccountId, Balance> {
+    /// The stash account of the validator.
+    pub stash: AccountId,
+    /// The validator's own stake.
+    pub own: Balance,
+    /// The total stake backing the validator, including its own.
+    pub total: Balance,
+    /// The number of guarantors backing the validator.
+    pub guarantors_count: u32,
+    /// The guarantors' share of the validator's rewards.
+    pub fee: Perbill,
+}
+
+/// The slashing records of a validator.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct ValidatorRisk {
+    /// The number of still-bonded eras in which the validator was slashed.
+    pub recent_offences: u32,
+    /// The number of slashing spans of the validator.
+    pub slashing_spans: u32,
+    /// Whether the validator has a slash waiting to be applied.
+    pub pending_slash: bool,
+}
+
+sp_api::decl_runtime_apis! {
+    pub trait StakingApi<AccountId, Balance> where
+        AccountId: Codec,
+        Balance: Codec,
+    {
+        /// The staking ratio of the active era, i.e. its total stakes over the total issuance,
+        /// and the annual inflation, i.e. the era's scheduled rewards over a year divided by
+        /// the total issuance.
+        fn inflation_info() -> (Perbill, Perbill);
+
+        /// The claim status of the given era's rewards.
+        fn era_payout_status(era: EraIndex) -> EraPayoutStatus;
+
+        /// The stake of the guarantor backing validators which have pending unapplied slashes.
+        fn at_risk_stake(guarantor: AccountId) -> Balance;
+
+        /// The rewards of the account for each era within `[from_era, to_era]`.
+        fn reward_history(who: AccountId, from_era: EraIndex, to_era: EraIndex) -> Vec<(EraIndex, Balance)>;
+
+        /// The number of guarantors grouped by how many targets they guarantee.
+        fn guarantee_target_histogram() -> Vec<(u32, u32)>;
+
+        /// The number of blocks authored by the validator in the given era.
+        fn blocks_authored(era: EraIndex, validator: AccountId) -> u32;
+
+        /// The workload a validator needs to reach the target stake limit.
+        fn workload_for_stake(target_stake: Balance) -> u128;
+
+        /// How much more stake the validator can take under its stake limit.
+        fn validator_headroom(validator: AccountId) -> Balance;
+
+        /// The validators actually backed by the guarantor in the active era, with the stake.
+        fn active_backing(guarantor: AccountId) -> Vec<(AccountId, Balance)>;
+
+        /// The elected validators of the current era, with their stake and preference.
+        fn current_validator_set() -> Vec<ValidatorInfo<AccountId, Balance>>;
+
+        /// The slashing records of the validator.
+        fn validator_risk(validator: AccountId) -> ValidatorRisk;
+
+        /// The current stake limit of the stash.
+        fn current_stake_limit(stash: AccountId) -> Option<Balance>;
+
+        /// The stake limit a validator would get with the given workload.
+        fn projected_stake_limit(workload: u128) -> Balance;
+
+        /// The deferred slashes of the stash not applied yet, by era.
+        fn pending_slashes_of(stash: AccountId) -> Vec<(EraIndex, Balance)>;
+
+        /// The reward points of the active era, the total and the points of each validator.
+        fn current_era_points() -> (u32, Vec<(AccountId, u32)>);
+    }
+}
@@ -0,0 +1,276 @@
+// Copyright (C) 2019-2021 Crust Network Technologies Ltd.
+// This file is part of Crust.
+
+//! RPC interface for the staking module.
+
+use std::{convert::TryFrom, sync::Arc};
+
+use codec::Codec;
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_rpc::number::NumberOrHex;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT, Perbill};
+
+pub use staking_rpc_runtime_api::{EraIndex, EraPayoutStatus, StakingApi as StakingRuntimeApi, ValidatorInfo, ValidatorRisk};
+
+#[rpc]
+pub trait StakingApi<BlockHash, AccountId> {
+    /// Get the staking ratio of the active era and the annual inflation of the reward schedule.
+    #[rpc(name = "staking_inflationInfo")]
+    fn inflation_info(&self, at: Option<BlockHash>) -> Result<(Perbill, Perbill)>;
+
+    /// Get the claim status of the given era's rewards.
+    #[rpc(name = "staking_eraPayoutStatus")]
+    fn era_payout_status(&self, era: EraIndex, at: Option<BlockHash>) -> Result<EraPayoutStatus>;
+
+    /// Get the stake of the guarantor backing validators which have pending unapplied slashes.
+    #[rpc(name = "staking_atRiskStake")]
+    fn at_risk_stake(&self, guarantor: AccountId, at: Option<BlockHash>) -> Result<NumberOrHex>;
+
+    /// Get the rewards of the account for each era within `[from_era, to_era]`.
+    #[rpc(name = "staking_rewardHistory")]
+    fn reward_history(
+        &self,
+        who: AccountId,
+        from_era: EraIndex,
+        to_era: EraIndex,
+        at: Option<BlockHash>
+    ) -> Result<Vec<(EraIndex, NumberOrHex)>>;
+
+    /// Get the number of guarantors grouped by how many targets they guarantee.
+    #[rpc(name = "staking_guaranteeTargetHistogram")]
+    fn guarantee_target_histogram(&self, at: Option<BlockHash>) -> Result<Vec<(u32, u32)>>;
+
+    /// Get the number of blocks authored by the validator in the given era.
+    #[rpc(name = "staking_blocksAuthored")]
+    fn blocks_authored(&self, era: EraIndex, validator: AccountId, at: Option<BlockHash>) -> Result<u32>;
+
+    /// Get the workload a validator needs to reach the target stake limit.
+    #[rpc(name = "staking_workloadForStake")]
+    fn workload_for_stake(&self, target_stake: NumberOrHex, at: Option<BlockHash>) -> Result<NumberOrHex>;
+
+    /// Get how much more stake the validator can take under its stake limit.
+    #[rpc(name = "staking_validatorHeadroom")]
+    fn validator_headroom(&self, validator: AccountId, at: Option<BlockHash>) -> Result<NumberOrHex>;
+
+    /// Get the validators actually backed by the guarantor in the active era, with the stake.
+    #[rpc(name = "staking_activeBacking")]
+    fn active_backing(&self, guarantor: AccountId, at: Option<BlockHash>) -> Result<Vec<(AccountId, NumberOrHex)>>;
+
+    /// Get the elected validators of the current era, with their stake and preference.
+    #[rpc(name = "staking_currentValidatorSet")]
+    fn current_validator_set(&self, at: Option<BlockHash>) -> Result<Vec<ValidatorInfo<AccountId, NumberOrHex>>>;
+
+    /// Get the slashing records of the validator.
+    #[rpc(name = "staking_validatorRisk")]
+    fn validator_risk(&self, validator: AccountId, at: Option<BlockHash>) -> Result<ValidatorRisk>;
+
+    /// Get the current stake limit of the stash.
+    #[rpc(name = "staking_currentStakeLimit")]
+    fn current_stake_limit(&self, stash: AccountId, at: Option<BlockHash>) -> Result<Option<NumberOrHex>>;
+
+    /// Get the stake limit a validator would get with the given workload.
+    #[rpc(name = "staking_projectedStakeLimit")]
+    fn projected_stake_limit(&self, workload: NumberOrHex, at: Option<BlockHash>) -> Result<NumberOrHex>;
+
+    /// Get the deferred slashes of the stash not applied yet, by era.
+    #[rpc(name = "staking_pendingSlashesOf")]
+    fn pending_slashes_of(&self, stash: AccountId, at: Option<BlockHash>) -> Result<Vec<(EraIndex, NumberOrHex)>>;
+
+    /// Get the reward points of the active era, the total and the points of each validator.
+    #[rpc(name = "staking_currentEraPoints")]
+    fn current_era_points(&self, at: Option<BlockHash>) -> Result<(u32, Vec<(AccountId, u32)>)>;
+}
+
+/// A struct that implements the [`StakingApi`].
+pub struct Staking<C, Block, AccountId, Balance> {
+    client: Arc<C>,
+    _marker: std::marker::PhantomData<(Block, AccountId, Balance)>,
+}
+
+impl<C, Block, AccountId, Balance> Staking<C, Block, AccountId, Balance> {
+    /// Create new `Staking` with the given reference to the client.
+    pub fn new(client: Arc<C>) -> Self {
+        Staking { client, _marker: Default::default() }
+    }
+}
+
+/// Error code of the runtime api call.
+const RUNTIME_ERROR: i64 = 1;
+
+fn runtime_error(e: impl std::fmt::Debug) -> RpcError {
+    RpcError {
+        code: ErrorCode::ServerError(RUNTIME_ERROR),
+        message: "Unable to query staking info.".into(),
+        data: Some(format!("{:?}", e).into()),
+    }
+}
+
+impl<C, Block, AccountId, Balance> StakingApi<<Block as BlockT>::Hash, AccountId>
+    for Staking<C, Block, AccountId, Balance>
+where
+    Block: BlockT,
+    C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+    C::Api: StakingRuntimeApi<Block, AccountId, Balance>,
+    AccountId: Codec + Send + Sync + 'static,
+    Balance: Codec + From<u128> + Into<NumberOrHex> + Send + Sync + 'static,
+{
+    fn inflation_info(&self, at: Option<<Block as BlockT>::Hash>) -> Result<(Perbill, Perbill)> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+        api.inflation_info(&at).map_err(runtime_error)
+    }
+
+    fn era_payout_status(&self, era: EraIndex, at: Option<<Block as BlockT>::Hash>) -> Result<EraPayoutStatus> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+        api.era_payout_status(&at, era).map_err(runtime_error)
+    }
+
+    fn at_risk_stake(&self, guarantor: AccountId, at: Option<<Block as BlockT>::Hash>) -> Result<NumberOrHex> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+        api.at_risk_stake(&at, guarantor).map(Into::into).map_err(runtime_error)
+    }
+
+    fn reward_history(
+        &self,
+        who: AccountId,
+        from_era: EraIndex,
+        to_era: EraIndex,
+        at: Option<<Block as BlockT>::Hash>
+    ) -> Result<Vec<(EraIndex, NumberOrHex)>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+        api.reward_history(&at, who, from_era, to_era)
+            .map(|rewards| rewards.into_iter().map(|(era, reward)| (era, reward.into())).collect())
+            .map_err(runtime_error)
+    }
+
+    fn guarantee_target_histogram(&self, at: Option<<Block as BlockT>::Hash>) -> Result<Vec<(u32, u32)>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+        api.guarantee_target_histogram(&at).map_err(runtime_error)
+    }
+
+    fn blocks_authored(
+        &self,
+        era: EraIndex,
+        validator: AccountId,
+        at: Option<<Block as BlockT>::Hash>
+    ) -> Result<u32> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+        api.blocks_authored(&at, era, validator).map_err(runtime_error)
+    }
+
+    fn workload_for_stake(
+        &self,
+        target_stake: NumberOrHex,
+        at: Option<<Block as BlockT>::Hash>
+    ) -> Result<NumberOrHex> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+        let target_stake = u128::try_from(target_stake)
+            .map_err(|_| RpcError::invalid_params("target_stake exceeds u128"))?;
+
+        api.workload_for_stake(&at, target_stake.into()).map(Into::into).map_err(runtime_error)
+    }
+
+    fn validator_headroom(&self, validator: AccountId, at: Option<<Block as BlockT>::Hash>) -> Result<NumberOrHex> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+        api.validator_headroom(&at, validator).map(Into::into).map_err(runtime_error)
+    }
+
+    fn active_backing(
+        &self,
+        guarantor: AccountId,
+        at: Option<<Block as BlockT>::Hash>
+    ) -> Result<Vec<(AccountId, NumberOrHex)>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+        api.active_backing(&at, guarantor)
+            .map(|backing| backing.into_iter().map(|(v_stash, value)| (v_stash, value.into())).collect())
+            .map_err(runtime_error)
+    }
+
+    fn current_validator_set(
+        &self,
+        at: Option<<Block as BlockT>::Hash>
+    ) -> Result<Vec<ValidatorInfo<AccountId, NumberOrHex>>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+        api.current_validator_set(&at)
+            .map(|validators| validators.into_iter().map(|v| ValidatorInfo {
+                stash: v.stash,
+                own: v.own.into(),
+                total: v.total.into(),
+                guarantors_count: v.guarantors_count,
+                fee: v.fee,
+            }).collect())
+            .map_err(runtime_error)
+    }
+
+    fn validator_risk(&self, validator: AccountId, at: Option<<Block as BlockT>::Hash>) -> Result<ValidatorRisk> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+        api.validator_risk(&at, validator).map_err(runtime_error)
+    }
+
+    fn current_stake_limit(
+        &self,
+        stash: AccountId,
+        at: Option<<Block as BlockT>::Hash>
+    ) -> Result<Option<NumberOrHex>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+        api.current_stake_limit(&at, stash).map(|limit| limit.map(Into::into)).map_err(runtime_error)
+    }
+
+    fn projected_stake_limit(
+        &self,
+        workload: NumberOrHex,
+        at: Option<<Block as BlockT>::Hash>
+    ) -> Result<NumberOrHex> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+        let workload = u128::try_from(workload)
+            .map_err(|_| RpcError::invalid_params("workload exceeds u128"))?;
+
+        api.projected_stake_limit(&at, workload).map(Into::into).map_err(runtime_error)
+    }
+
+    fn pending_slashes_of(
+        &self,
+        stash: AccountId,
+        at: Option<<Block as BlockT>::Hash>
+    ) -> Result<Vec<(EraIndex, NumberOrHex)>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+        api.pending_slashes_of(&at, stash)
+            .map(|slashes| slashes.into_iter().map(|(era, value)| (era, value.into())).collect())
+            .map_err(runtime_error)
+    }
+
+    fn current_era_points(&self, at: Option<<Block as BlockT>::Hash>) -> Result<(u32, Vec<(AccountId, u32)>)> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+        api.current_era_points(&at).map_err(runtime_error)
+    }
+}
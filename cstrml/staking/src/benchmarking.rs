@@ -47,7 +47,7 @@ pub fn create_stash_controller<T: Config>(n: u32) -> Result<(T::AccountId, T::Ac
     return Ok((stash, controller))
 }
 
-// This function generates v validators and n guarantor who are randomly nominating up to MAX_GUARANTEE.
+// This function generates v validators and n guarantor who are randomly nominating up to MaxGuarantees.
 pub fn create_validators_with_guarantors_for_era<T: Config>(v: u32, n: u32, m: u32) -> Result<(T::AccountId, <T::Lookup as StaticLookup>::Source), &'static str> {
     let mut validators: Vec<<T::Lookup as StaticLookup>::Source> = Vec::with_capacity(v as usize);
     let mut rng = ChaChaRng::from_seed(SEED.using_encoded(blake2_256));
@@ -155,13 +155,13 @@ benchmarks! {
 
     guarantee {
         MinimumValidatorCount::put(1);
-        let (g_controller, v_lookup) = create_validators_with_guarantors_for_era::<T>(100, 1000, MAX_GUARANTEE.try_into().unwrap())?;
+        let (g_controller, v_lookup) = create_validators_with_guarantors_for_era::<T>(100, 1000, T::MaxGuarantees::get())?;
     }: _(RawOrigin::Signed(g_controller), (v_lookup, T::Currency::minimum_balance() * 10u32.into()))
 
 
     cut_guarantee {
         MinimumValidatorCount::put(1);
-        let (g_controller, v_lookup) = create_validators_with_guarantors_for_era::<T>(100, 1000, MAX_GUARANTEE.try_into().unwrap())?;
+        let (g_controller, v_lookup) = create_validators_with_guarantors_for_era::<T>(100, 1000, T::MaxGuarantees::get())?;
         Staking::<T>::guarantee(RawOrigin::Signed(g_controller.clone()).into(),
         (v_lookup.clone(), T::Currency::minimum_balance() * 10u32.into()))?;
     }: _(RawOrigin::Signed(g_controller), (v_lookup, T::Currency::minimum_balance() * 10u32.into()))
@@ -180,11 +180,20 @@ benchmarks! {
         assert!(Ledger::<T>::contains_key(&new_controller));
     }
 
+    // Bonding sets the payee to `Staked`, the heaviest one, it updates the ledger and the lock
+    claim_accrued {
+        let (stash, controller) = create_stash_controller::<T>(100)?;
+        PendingRewards::<T>::insert(&stash, T::Currency::minimum_balance() * 10u32.into());
+    }: _(RawOrigin::Signed(controller))
+    verify {
+        assert!(!PendingRewards::<T>::contains_key(&stash));
+    }
+
 
     new_era {
         let v in 1 .. 2;
         let n in 1 .. 2;
-        let m in 1 .. MAX_GUARANTEE.try_into().unwrap();
+        let m in 1 .. T::MaxGuarantees::get();
         MinimumValidatorCount::put(1);
         create_validators_with_guarantors_for_era::<T>(10u32.pow(v), 10u32.pow(n), m)?;
         let session_index = SessionIndex::one();
@@ -195,7 +204,7 @@ benchmarks! {
     select_and_update_validators {
         let v in 1 .. 2;
         let n in 1 .. 2;
-        let m in 1 .. MAX_GUARANTEE.try_into().unwrap();
+        let m in 1 .. T::MaxGuarantees::get();
         MinimumValidatorCount::put(1);
         create_validators_with_guarantors_for_era::<T>(10u32.pow(v), 10u32.pow(n), m)?;
         let session_index = SessionIndex::one();
@@ -241,6 +250,7 @@ mod tests {
             assert_ok!(test_benchmark_select_and_update_validators::<Test>());
             assert_ok!(test_benchmark_chill::<Test>());
             assert_ok!(test_benchmark_set_controller::<Test>());
+            assert_ok!(test_benchmark_claim_accrued::<Test>());
             assert_ok!(test_benchmark_withdraw_unbonded::<Test>());
         });
     }
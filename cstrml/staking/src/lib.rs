@@ -20,7 +20,7 @@ use total_stake_limit_ratio::total_stake_limit_ratio;
 use frame_support::{
     decl_module, decl_event, decl_storage, ensure, decl_error,
     storage::IterableStorageMap,
-    weights::{Weight, constants::{WEIGHT_PER_MICROS, WEIGHT_PER_NANOS}},
+    weights::{Weight, DispatchClass, constants::{WEIGHT_PER_MICROS, WEIGHT_PER_NANOS}},
     traits::{
         Currency, LockIdentifier, LockableCurrency, WithdrawReasons, OnUnbalanced, Imbalance, Get,
         UnixTime, EnsureOrigin, Randomness
@@ -30,9 +30,10 @@ use frame_support::{
 use pallet_session::historical;
 use sp_runtime::{
     Perbill, Permill, RuntimeDebug, SaturatedConversion, ModuleId,
+    helpers_128bit::multiply_by_rational,
     traits::{
         Convert, Zero, One, StaticLookup, Saturating, AtLeast32Bit,
-        CheckedAdd, CheckedSub, AtLeast32BitUnsigned
+        CheckedAdd, CheckedSub, AtLeast32BitUnsigned, Bounded
     },
 };
 use sp_staking::{
@@ -50,6 +51,7 @@ pub mod weight;
 
 // Crust runtime modules
 use swork;
+pub use staking_rpc_runtime_api::{EraPayoutStatus, ValidatorInfo, ValidatorRisk};
 use primitives::{
     EraIndex,
     constants::{currency::*, time::*, staking::*},
@@ -58,7 +60,8 @@ use primitives::{
 
 const DEFAULT_MINIMUM_VALIDATOR_COUNT: u32 = 4;
 const MAX_UNLOCKING_CHUNKS: usize = 32;
-const MAX_GUARANTEE: usize = 16;
+const MAX_FORCE_UNSTAKE_BATCH: usize = 64;
+const MAX_STAKE_LIMITS_BATCH: usize = 64;
 const STAKING_ID: LockIdentifier = *b"staking ";
 
 pub(crate) const LOG_TARGET: &'static str = "staking";
@@ -85,6 +88,7 @@ pub trait WeightInfo {
     fn chill() -> Weight;
     fn set_payee() -> Weight;
     fn set_controller() -> Weight;
+    fn claim_accrued() -> Weight;
     // The following two doesn't used to generate weight info
     fn new_era(v: u32, n: u32, m: u32, ) -> Weight;
     fn select_and_update_validators(v: u32, n: u32, m: u32, ) -> Weight;
@@ -245,6 +249,23 @@ impl<AccountId, Balance: HasCompact + Copy + Saturating + AtLeast32BitUnsigned>
         }
     }
 
+    /// Merge the `unlocking` chunks of the same era into one, keeping the order of
+    /// their first appearances. Returns the ledger and the number of removed chunks.
+    fn consolidate_chunks(mut self) -> (Self, u32) {
+        let old_len = self.unlocking.len();
+        let mut unlocking: Vec<UnlockChunk<Balance>> = Vec::with_capacity(old_len);
+        for chunk in self.unlocking.into_iter() {
+            if let Some(merged) = unlocking.iter_mut().find(|c| c.era == chunk.era) {
+                merged.value = merged.value.saturating_add(chunk.value);
+            } else {
+                unlocking.push(chunk);
+            }
+        }
+        self.unlocking = unlocking;
+        let removed = (old_len - self.unlocking.len()) as u32;
+        (self, removed)
+    }
+
     /// Re-bond funds that were scheduled for unlocking.
     fn rebond(mut self, value: Balance) -> Self {
         let mut unlocking_balance: Balance = Zero::zero();
@@ -480,6 +501,37 @@ pub trait Config: frame_system::Config {
     /// Used for bonding buffer
     type UncheckedFrozenBondFund: Get<BalanceOf<Self>>;
 
+    /// Number of eras after bonding during which a stash can not be slashed.
+    type SlashingImmunityEras: Get<EraIndex>;
+
+    /// Reward points for authoring a block, referencing an uncle and producing an uncle.
+    type AuthoringPoints: Get<(RewardPoint, RewardPoint, RewardPoint)>;
+
+    /// Maximum total stake of a validator's exposure, `None` means no cap.
+    type MaxStakePerValidator: Get<Option<BalanceOf<Self>>>;
+
+    /// Minimum ratio of a validator's own stake to its total exposure to be elected.
+    type MinSelfStakeRatio: Get<Perbill>;
+
+    /// Maximum increase of a validator's commission, i.e. one minus the `fee` of its prefs,
+    /// within an era.
+    type MaxCommissionIncreasePerEra: Get<Perbill>;
+
+    /// What to do when there are not enough candidates to elect.
+    type Fallback: Get<FallbackMode>;
+
+    /// Maximum number of validators a guarantor can guarantee.
+    type MaxGuarantees: Get<u32>;
+
+    /// The maximum share of the rewards a validator can take, i.e. one minus the `fee` of its prefs.
+    type MaxCommission: Get<Perbill>;
+
+    /// The minimum active bond to become a validator.
+    type MinValidatorBond: Get<BalanceOf<Self>>;
+
+    /// The minimum active bond to become a guarantor.
+    type MinGuarantorBond: Get<BalanceOf<Self>>;
+
     /// Weight information for extrinsics in this pallet.
     type WeightInfo: WeightInfo;
 }
@@ -504,6 +556,16 @@ impl Default for Forcing {
     }
 }
 
+/// Mode of the election when there are not enough candidates.
+#[derive(Copy, Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug)]
+pub enum FallbackMode {
+    /// Keep the elected validators and their exposures of the previous era, the exposures of
+    /// the other candidates are not kept.
+    KeepCurrentSet,
+    /// Elect nobody, the session keeps its validators but the era has no exposures.
+    StallElection,
+}
+
 decl_storage! {
     trait Store for Module<T: Config> as Staking {
         /// Number of eras to keep in history.
@@ -538,6 +600,9 @@ decl_storage! {
         pub StakeLimit get(fn stake_limit):
             map hasher(twox_64_concat) T::AccountId => Option<BalanceOf<T>>;
 
+        /// The total workload in the last `report_works`, used to estimate the stake limit.
+        pub TotalWorkload get(fn total_workload): u128;
+
         /// Exposure of validator at era.
         ///
         /// This is keyed first by the era index to allow bulk deletion and then the stash account.
@@ -625,6 +690,10 @@ decl_storage! {
         pub ErasRewardPoints get(fn eras_reward_points):
         map hasher(twox_64_concat) EraIndex => EraRewardPoints<T::AccountId>;
 
+        /// The number of blocks authored by each validator for the last `HISTORY_DEPTH` eras.
+        pub ErasBlocksAuthored get(fn eras_blocks_authored):
+            double_map hasher(twox_64_concat) EraIndex, hasher(twox_64_concat) T::AccountId => u32;
+
         /// True if the next session change will be a new era regardless of index.
         pub ForceEra get(fn force_era) config(): Forcing;
 
@@ -667,6 +736,29 @@ decl_storage! {
         /// The earliest era for which we have a pending, unapplied slash.
         EarliestUnappliedSlash: Option<EraIndex>;
 
+        /// The number of validators that haven't claimed their rewards of the era yet.
+        ///
+        /// Eras before this was introduced have no entry, their claim status is unknown.
+        pub ErasUnclaimedValidators get(fn eras_unclaimed_validators):
+            map hasher(twox_64_concat) EraIndex => u32;
+
+        /// The era at which a stash was bonded, used for the slashing immunity window.
+        pub BondedAt get(fn bonded_at): map hasher(twox_64_concat) T::AccountId => Option<EraIndex>;
+
+        /// The era of the latest commission change of a validator and its commission before
+        /// that era's first change, used to limit the commission increase within an era.
+        pub CommissionChangedAt get(fn commission_changed_at):
+            map hasher(twox_64_concat) T::AccountId => Option<(EraIndex, Perbill)>;
+
+        /// The bonding duration set by governance, overrides `BondingDuration` if any.
+        pub BondingDurationOverride get(fn bonding_duration_override): Option<EraIndex>;
+
+        /// The guarantors whose rewards are accrued into `PendingRewards` instead of being paid.
+        pub AccrueRewards get(fn accrue_rewards): map hasher(twox_64_concat) T::AccountId => bool;
+
+        /// The accrued rewards of the guarantors, waiting to be claimed.
+        pub PendingRewards get(fn pending_rewards): map hasher(twox_64_concat) T::AccountId => BalanceOf<T>;
+
         /// Whitelist candidates to be validators
         ValidatorsWhitelist get(fn validators_whitelist): Vec<T::AccountId>;
 
@@ -688,6 +780,8 @@ decl_storage! {
                     T::Lookup::unlookup(controller.clone()),
                     balance
                 );
+                // Genesis stakers are active from era 0, they don't get the slashing immunity
+                <BondedAt<T>>::remove(stash);
 
                 gensis_total_stakes += balance;
 
@@ -742,6 +836,9 @@ decl_event!(
         /// An account has called `withdraw_unbonded` and removed unbonding chunks worth `Balance`
         /// from the unlocking queue. [stash, amount]
         Withdrawn(AccountId, Balance),
+        /// An account has called `withdraw_unbonded_partial` and removed unbonding chunks worth
+        /// `Balance` from the unlocking queue. [stash, amount]
+        PartialWithdrawal(AccountId, Balance),
         /// An account has called `validate` and set guarantee fee.
         ValidateSuccess(AccountId, ValidatorPrefs),
         /// An account has called `guarantee` and vote for one validator.
@@ -752,6 +849,51 @@ decl_event!(
         ChillSuccess(AccountId, AccountId),
         /// Update the identities success. The stake limit of each identity would be updated.
         UpdateStakeLimitSuccess(u32),
+        /// An offence was ignored since the stash is still in its slashing immunity window.
+        OffenceSkippedImmunity(AccountId),
+        /// The guarantors' stakes of a validator were trimmed by this amount to fit the cap.
+        StakeCappedForValidator(AccountId, Balance),
+        /// A deferred slash reported in the era was applied to the validator and its
+        /// guarantors, for this total amount. [era, validator, amount]
+        DeferredSlashApplied(EraIndex, AccountId, Balance),
+        /// The target's stake already meets or exceeds its stake limit, the new votes of the
+        /// guarantor will be trimmed at the next election. [guarantor, validator]
+        TargetAtLimit(AccountId, AccountId),
+        /// The validator's own stake is below `MinSelfStakeRatio` of its exposure,
+        /// so it is excluded from the election. [validator]
+        ValidatorExcludedLowSelfStake(AccountId),
+        /// The unlocking chunks of the same era have been merged. [stash, removed chunks]
+        ChunksConsolidated(AccountId, u32),
+        /// There are not enough candidates to elect, `Fallback` is applied. [found, needed]
+        InsufficientCandidates(u32, u32),
+        /// The bonding duration has been changed by governance. [bonding duration]
+        BondingDurationSet(EraIndex),
+        /// The guarantor's reward has been accrued instead of paid. [stash, amount]
+        RewardsAccrued(AccountId, Balance),
+        /// The guarantor has claimed all the accrued rewards. [stash, amount]
+        RewardsClaimed(AccountId, Balance),
+        /// The stashes have been force unstaked by governance. [count]
+        ForceUnstakeBatch(u32),
+        /// A new era is planned with this number of validators. [era, validators count]
+        NewEra(EraIndex, u32),
+        /// The stakes backing the validator exceed its stake limit and are reduced.
+        /// [validator, total stakes, valid stakes]
+        StakeLimitReduced(AccountId, Balance, Balance),
+        /// The guarantor's stake on the validator is reduced by the validator's stake limit,
+        /// or trimmed to fit `MaxStakePerValidator`. [guarantor, validator, votes, valid stake]
+        GuarantorStakeReduced(AccountId, AccountId, Balance, Balance),
+        /// A validator without stake limit has been chilled by someone else. [who, stash]
+        ChilledOther(AccountId, AccountId),
+        /// A guarantor has been kicked from a validator. [guarantor, validator]
+        Kicked(AccountId, AccountId),
+        /// The validator has no bonded ledger, so it is excluded from the election. [validator]
+        ValidatorExcludedNoLedger(AccountId),
+        /// All the funds of the stash are withdrawn and its staking information is removed. [stash]
+        StashReaped(AccountId),
+        /// The stake limits of this number of stashes have been set by root. [count]
+        StakeLimitsSet(u32),
+        /// The reward points earned in the ended era. [era, total, points of each validator]
+        EraPoints(EraIndex, RewardPoint, Vec<(AccountId, RewardPoint)>),
     }
 );
 
@@ -792,6 +934,27 @@ decl_error! {
         NoUnlockChunk,
         /// Staking locks need to be the maximum locks
         InsufficientFrozenBond,
+        /// Increase the commission more than `MaxCommissionIncreasePerEra` within an era.
+        CommissionIncreaseTooFast,
+        /// The bonding duration exceeds `BondingDuration` or doesn't exceed `SlashDeferDuration`.
+        InvalidBondingDuration,
+        /// There are no accrued rewards to claim.
+        NoAccruedRewards,
+        /// The accrued rewards can't be paid to the payee.
+        AccruedPayoutFailed,
+        /// The stash is not a validator or its stake limit is not zero, it can't be chilled
+        /// by others.
+        CannotChillOther,
+        /// The validator would take more than `MaxCommission` of the rewards.
+        CommissionTooHigh,
+        /// The bonded funds of the stash are above the existential deposit, it can't be reaped.
+        FundsStillBonded,
+        /// The stash is not a validator.
+        NotValidator,
+        /// The active bond is below `MinValidatorBond` or `MinGuarantorBond`.
+        InsufficientBond,
+        /// Too many stake limits to set in one call.
+        TooManyStakeLimits,
     }
 }
 
@@ -827,6 +990,18 @@ decl_module! {
 
         const UncheckedFrozenBondFund: BalanceOf<T> = T::UncheckedFrozenBondFund::get();
 
+        /// Number of eras after bonding during which a stash can not be slashed.
+        const SlashingImmunityEras: EraIndex = T::SlashingImmunityEras::get();
+
+        /// Maximum number of validators a guarantor can guarantee.
+        const MaxGuarantees: u32 = T::MaxGuarantees::get();
+
+        /// The minimum active bond to become a validator.
+        const MinValidatorBond: BalanceOf<T> = T::MinValidatorBond::get();
+
+        /// The minimum active bond to become a guarantor.
+        const MinGuarantorBond: BalanceOf<T> = T::MinGuarantorBond::get();
+
         type Error = Error<T>;
 
         fn deposit_event() = default;
@@ -896,6 +1071,7 @@ decl_module! {
             <Payee<T>>::insert(&stash, RewardDestination::Staked);
 
             let current_era = CurrentEra::get().unwrap_or(0);
+            <BondedAt<T>>::insert(&stash, current_era);
             let history_depth = Self::history_depth();
             let last_reward_era = current_era.saturating_sub(history_depth);
 
@@ -1011,8 +1187,11 @@ decl_module! {
                 }
 
                 // Note: in case there is no current era it is fine to bond one era more.
-                let era = Self::current_era().unwrap_or(0) + T::BondingDuration::get();
-                ledger.unlocking.push(UnlockChunk { value, era });
+                let era = Self::current_era().unwrap_or(0) + Self::bonding_duration();
+                // Keep the chunks ordered by era, the bonding duration can be shortened
+                let pos = ledger.unlocking.iter().position(|chunk| chunk.era > era)
+                    .unwrap_or(ledger.unlocking.len());
+                ledger.unlocking.insert(pos, UnlockChunk { value, era });
                 Self::update_ledger(&controller, &ledger);
                 Self::deposit_event(RawEvent::Unbonded(ledger.stash, value));
             }
@@ -1058,7 +1237,7 @@ decl_module! {
         /// The dispatch origin for this call must be _Signed_ by the controller, not the stash.
         /// And, it can be only called when [`EraElectionStatus`] is `Closed`.
         ///
-        /// Emits `Withdrawn`.
+        /// Emits `Withdrawn`, and `StashReaped` if nothing is left bonded.
         ///
         /// See also [`Call::unbond`].
         ///
@@ -1082,37 +1261,120 @@ decl_module! {
         #[weight = T::WeightInfo::withdraw_unbonded()]
         fn withdraw_unbonded(origin) {
             let controller = ensure_signed(origin)?;
-            let mut ledger = Self::ledger(&controller).ok_or(Error::<T>::NotController)?;
-            let (stash, old_total) = (ledger.stash.clone(), ledger.total);
-            if let Some(current_era) = Self::current_era() {
-                // remove the lock first, update_ledger would add the lock back anyway
-                T::Currency::remove_lock(STAKING_ID, &stash);
-                let frozen_balance = T::Currency::frozen_balance(&stash);
-                ledger = ledger.consolidate_unlocked(current_era, frozen_balance);
+            let (stash, withdrawn, reaped) = Self::do_withdraw_unbonded(&controller, None)?;
+
+            if !withdrawn.is_zero() {
+                Self::deposit_event(RawEvent::Withdrawn(stash.clone(), withdrawn));
+            }
+            if reaped {
+                Self::deposit_event(RawEvent::StashReaped(stash));
             }
+        }
 
-            if ledger.unlocking.is_empty() && ledger.active.is_zero() {
-                // This account must have called `unbond()` with some value that caused the active
-                // portion to fall below existential deposit + will have no more unlocking chunks
-                // left. We can now safely remove all staking-related information.
-                Self::kill_stash(&stash)?;
-            } else {
-                // This was the consequence of a partial unbond. just update the ledger and move on.
-                Self::update_ledger(&controller, &ledger);
+        /// Remove at most `value` of the unlocked chunks from the `unlocking` queue.
+        ///
+        /// Works like [`Call::withdraw_unbonded`], but anything unlocked above `value` is
+        /// re-queued as an already unlocked chunk instead of being freed. If `value` exceeds
+        /// the unlocked funds, all of them are withdrawn.
+        ///
+        /// The dispatch origin for this call must be _Signed_ by the controller, not the stash.
+        ///
+        /// Emits `PartialWithdrawal`, and `StashReaped` if nothing is left bonded.
+        ///
+        /// # <weight>
+        /// Same as [`Call::withdraw_unbonded`]: the same storage items are read and written,
+        /// and the re-queued remainder is merged into an unlocked chunk when there is one, so
+        /// `Ledger.unlocking` never grows past what `withdraw_unbonded` would leave.
+        /// # </weight>
+        #[weight = T::WeightInfo::withdraw_unbonded()]
+        fn withdraw_unbonded_partial(origin, #[compact] value: BalanceOf<T>) {
+            let controller = ensure_signed(origin)?;
+            let (stash, withdrawn, reaped) = Self::do_withdraw_unbonded(&controller, Some(value))?;
+
+            if !withdrawn.is_zero() {
+                Self::deposit_event(RawEvent::PartialWithdrawal(stash.clone(), withdrawn));
+            }
+            if reaped {
+                Self::deposit_event(RawEvent::StashReaped(stash));
             }
+        }
 
-            // `old_total` should never be less than the new total because
-            // `consolidate_unlocked` strictly subtracts balance.
-            if ledger.total < old_total {
-                // Already checked that this won't overflow by entry condition.
-                let value = old_total - ledger.total;
-                Self::deposit_event(RawEvent::Withdrawn(stash, value));
+        /// Merge the unlocking chunks of the same era into one chunk, to free the
+        /// `unlocking` queue of the origin's ledger.
+        ///
+        /// The dispatch origin for this call must be _Signed_ by the controller, not the stash.
+        ///
+        /// Emits `ChunksConsolidated`.
+        ///
+        /// # <weight>
+        /// - Time complexity: O(L^2), where L is unlocking chunks
+        /// - Bounded by `MAX_UNLOCKING_CHUNKS`.
+        /// - Storage changes: Can't increase storage, only decrease it.
+        /// # </weight>
+        #[weight = T::WeightInfo::rebond(MAX_UNLOCKING_CHUNKS as u32)]
+        fn consolidate_chunks(origin) {
+            let controller = ensure_signed(origin)?;
+            let ledger = Self::ledger(&controller).ok_or(Error::<T>::NotController)?;
+            let (ledger, removed) = ledger.consolidate_chunks();
+            if removed > 0 {
+                <Ledger<T>>::insert(&controller, &ledger);
             }
+            Self::deposit_event(RawEvent::ChunksConsolidated(ledger.stash, removed));
+        }
+
+        /// Opt in or out of accruing the guarantee rewards, then the rewards paid by
+        /// `reward_stakers` are kept in `PendingRewards` until `claim_accrued` is called.
+        ///
+        /// The dispatch origin for this call must be _Signed_ by the controller, not the stash.
+        ///
+        /// # <weight>
+        /// - Independent of the arguments. Insignificant complexity.
+        /// ---------
+        /// - DB Weight:
+        ///     - Read: Ledger
+        ///     - Write: AccrueRewards
+        /// # </weight>
+        #[weight = T::WeightInfo::set_payee()]
+        fn set_accrue_rewards(origin, enable: bool) {
+            let controller = ensure_signed(origin)?;
+            let ledger = Self::ledger(&controller).ok_or(Error::<T>::NotController)?;
+            if enable {
+                <AccrueRewards<T>>::insert(&ledger.stash, true);
+            } else {
+                <AccrueRewards<T>>::remove(&ledger.stash);
+            }
+        }
+
+        /// Pay all the accrued rewards of the origin's stash to its payee.
+        ///
+        /// The dispatch origin for this call must be _Signed_ by the controller, not the stash.
+        ///
+        /// Emits `RewardsClaimed`.
+        ///
+        /// # <weight>
+        /// - Independent of the arguments. Insignificant complexity.
+        /// ---------
+        /// - DB Weight:
+        ///     - Read: Ledger, PendingRewards, Payee, Bonded, [Payee Account]
+        ///     - Write: PendingRewards, [Payee Account], (Ledger, Locks if `Staked`)
+        /// # </weight>
+        #[weight = T::WeightInfo::claim_accrued()]
+        fn claim_accrued(origin) {
+            let controller = ensure_signed(origin)?;
+            let ledger = Self::ledger(&controller).ok_or(Error::<T>::NotController)?;
+            let stash = ledger.stash;
+            let amount = Self::pending_rewards(&stash);
+            ensure!(!amount.is_zero(), Error::<T>::NoAccruedRewards);
+
+            let imbalance = Self::make_payout(&stash, amount).ok_or(Error::<T>::AccruedPayoutFailed)?;
+            <PendingRewards<T>>::remove(&stash);
+            Self::deposit_event(RawEvent::RewardsClaimed(stash, imbalance.peek()));
         }
 
         /// Declare the desire to validate for the origin controller.
         ///
-        /// Effects will be felt at the beginning of the next era.
+        /// Effects will be felt at the beginning of the next era. The commission, i.e. one minus
+        /// the fee, can't be increased by more than `MaxCommissionIncreasePerEra` within an era.
         ///
         /// The dispatch origin for this call must be _Signed_ by the controller, not the stash.
         ///
@@ -1122,14 +1384,39 @@ decl_module! {
         /// - Writes are limited to the `origin` account key.
         /// -----------
         /// DB Weight:
-        /// - Read: Ledger, StakeLimit
-        /// - Write: Guarantors, Validators
+        /// - Read: Ledger, StakeLimit, CurrentEra, CommissionChangedAt
+        /// - Write: Guarantors, Validators, CommissionChangedAt
         /// # </weight>
         #[weight = T::WeightInfo::validate()]
         fn validate(origin, prefs: ValidatorPrefs) {
             let controller = ensure_signed(origin)?;
             let ledger = Self::ledger(&controller).ok_or(Error::<T>::NotController)?;
             let v_stash = &ledger.stash;
+            ensure!(ledger.active >= T::MinValidatorBond::get(), Error::<T>::InsufficientBond);
+
+            // The validator takes `1 - fee` of the rewards
+            ensure!(
+                prefs.fee >= Perbill::one().saturating_sub(T::MaxCommission::get()),
+                Error::<T>::CommissionTooHigh
+            );
+
+            // Limit the commission increase within the era, decrease is always allowed
+            let current_era = Self::current_era().unwrap_or(0);
+            let maybe_base_commission = match Self::commission_changed_at(v_stash) {
+                Some((era, base_commission)) if era == current_era => Some(base_commission),
+                _ if <Validators<T>>::contains_key(v_stash) =>
+                    Some(Perbill::one().saturating_sub(Self::validators(v_stash).fee)),
+                _ => None,
+            };
+            if let Some(base_commission) = maybe_base_commission {
+                let commission = Perbill::one().saturating_sub(prefs.fee);
+                ensure!(
+                    commission <= base_commission.saturating_add(T::MaxCommissionIncreasePerEra::get()),
+                    Error::<T>::CommissionIncreaseTooFast
+                );
+                <CommissionChangedAt<T>>::insert(v_stash, (current_era, base_commission));
+            }
+
             <Guarantors<T>>::remove(v_stash);
             <Validators<T>>::insert(v_stash, &prefs);
             // Set the validator pref to 100% for the ongoing era as the punishment
@@ -1161,6 +1448,7 @@ decl_module! {
             let ledger = Self::ledger(&controller).ok_or(Error::<T>::NotController)?;
             let g_stash = &ledger.stash;
             let (target, votes) = target;
+            ensure!(ledger.active >= T::MinGuarantorBond::get(), Error::<T>::InsufficientBond);
 
             // 2. Target should be legal
             let v_stash = T::Lookup::lookup(target)?;
@@ -1172,10 +1460,18 @@ decl_module! {
             // 4. Upsert (increased) guarantee
             let guarantee = Self::increase_guarantee(&v_stash, g_stash, ledger.active.clone(), votes.clone());
 
-            // 5. `None` means exceed the guarantee limit(`MAX_GUARANTEE`)
+            // 5. `None` means exceed the guarantee limit(`MaxGuarantees`)
             ensure!(guarantee.is_some(), Error::<T>::ExceedGuaranteeLimit);
             let guarantee = guarantee.unwrap();
 
+            // 6. Warn if the target's stake already meets its stake limit, the new votes won't count
+            let current_era = Self::current_era().unwrap_or(0);
+            let backing = Self::eras_stakers(current_era, &v_stash).total;
+            let stake_limit = Self::stake_limit(&v_stash).unwrap_or_default();
+            if backing >= stake_limit {
+                Self::deposit_event(RawEvent::TargetAtLimit(g_stash.clone(), v_stash.clone()));
+            }
+
             <Validators<T>>::remove(g_stash);
             <Guarantors<T>>::insert(g_stash, guarantee);
             Self::deposit_event(RawEvent::GuaranteeSuccess(controller, v_stash, votes));
@@ -1244,6 +1540,57 @@ decl_module! {
             Self::deposit_event(RawEvent::ChillSuccess(controller, ledger.stash));
         }
 
+        /// Chill a validator whose stake limit has dropped to zero, so it's no longer
+        /// a candidate before the next era.
+        ///
+        /// The dispatch origin for this call can be any _Signed_ account.
+        ///
+        /// # <weight>
+        /// Same as [`Call::chill`].
+        /// # </weight>
+        #[weight = T::WeightInfo::chill()]
+        fn chill_other(origin, stash: T::AccountId) {
+            let who = ensure_signed(origin)?;
+            ensure!(<Validators<T>>::contains_key(&stash), Error::<T>::CannotChillOther);
+            ensure!(Self::stake_limit(&stash).unwrap_or_default().is_zero(), Error::<T>::CannotChillOther);
+            Self::chill_stash(&stash);
+            Self::deposit_event(RawEvent::ChilledOther(who, stash));
+        }
+
+        /// Remove the guarantees of the given guarantors on the origin's validator.
+        ///
+        /// Effects will be felt at the beginning of the next era.
+        ///
+        /// The dispatch origin for this call must be _Signed_ by the controller, not the stash.
+        ///
+        /// - `who`: The guarantors to kick, the ones not guaranteeing the validator are skipped.
+        ///
+        /// # <weight>
+        /// Same as [`Call::cut_guarantee`] for each of `who`.
+        /// # </weight>
+        #[weight = T::WeightInfo::cut_guarantee().saturating_mul(who.len() as Weight)]
+        fn kick(origin, who: Vec<<T::Lookup as StaticLookup>::Source>) {
+            let controller = ensure_signed(origin)?;
+            let ledger = Self::ledger(&controller).ok_or(Error::<T>::NotController)?;
+            let v_stash = &ledger.stash;
+            ensure!(<Validators<T>>::contains_key(v_stash), Error::<T>::NotValidator);
+
+            // Resolve all of them first, nothing is kicked if one of them fails
+            let who = who.into_iter()
+                .map(T::Lookup::lookup)
+                .collect::<Result<Vec<T::AccountId>, _>>()?;
+            for g_stash in who.into_iter() {
+                if let Some(guarantee) = Self::decrease_guarantee(v_stash, &g_stash, BalanceOf::<T>::max_value()) {
+                    if guarantee.targets.is_empty() {
+                        <Guarantors<T>>::remove(&g_stash);
+                    } else {
+                        <Guarantors<T>>::insert(&g_stash, guarantee);
+                    }
+                    Self::deposit_event(RawEvent::Kicked(g_stash, v_stash.clone()));
+                }
+            }
+        }
+
         /// (Re-)set the controller of a stash.
         ///
         /// Effects will be felt at the beginning of the next era.
@@ -1389,6 +1736,55 @@ decl_module! {
             Self::kill_stash(&stash)?;
         }
 
+        /// Force a list of current stakers to become completely unstaked, immediately.
+        ///
+        /// The dispatch origin must be Root.
+        ///
+        /// At most `MAX_FORCE_UNSTAKE_BATCH` stashes are processed per call, the rest of the
+        /// list is ignored and should be re-submitted. Accounts which are not stashes are skipped.
+        ///
+        /// # <weight>
+        /// Same as `force_unstake` for each processed stash.
+        /// # </weight>
+        #[weight = T::DbWeight::get().reads_writes(4, 7)
+            .saturating_add(53 * WEIGHT_PER_MICROS)
+            .saturating_mul(stashes.len().min(MAX_FORCE_UNSTAKE_BATCH) as Weight)]
+        fn force_unstake_batch(origin, stashes: Vec<T::AccountId>) {
+            ensure_root(origin)?;
+
+            let mut count: u32 = 0;
+            for stash in stashes.iter().take(MAX_FORCE_UNSTAKE_BATCH) {
+                if Self::kill_stash(stash).is_ok() {
+                    T::Currency::remove_lock(STAKING_ID, stash);
+                    count += 1;
+                }
+            }
+            Self::deposit_event(RawEvent::ForceUnstakeBatch(count));
+        }
+
+        /// Set the stake limits of the given stashes, for testnets and migrations where
+        /// no workload is reported.
+        ///
+        /// The dispatch origin must be Root.
+        ///
+        /// At most `MAX_STAKE_LIMITS_BATCH` stake limits can be set per call.
+        ///
+        /// # <weight>
+        /// - One write for each of `limits`.
+        /// # </weight>
+        #[weight = T::DbWeight::get().writes(limits.len().min(MAX_STAKE_LIMITS_BATCH) as Weight)
+            .saturating_add(1000)]
+        fn set_stake_limits(origin, limits: Vec<(T::AccountId, BalanceOf<T>)>) {
+            ensure_root(origin)?;
+            ensure!(limits.len() <= MAX_STAKE_LIMITS_BATCH, Error::<T>::TooManyStakeLimits);
+
+            let count = limits.len() as u32;
+            for (stash, limit) in limits.iter() {
+                Self::upsert_stake_limit(stash, *limit);
+            }
+            Self::deposit_event(RawEvent::StakeLimitsSet(count));
+        }
+
         /// Force there to be a new era at the end of sessions indefinitely.
         ///
         /// The dispatch origin must be Root.
@@ -1447,13 +1843,38 @@ decl_module! {
             <Self as Store>::UnappliedSlashes::insert(&era, &unapplied);
         }
 
+        /// Cancel enactment of the deferred slashes of the given offending validators.
+        ///
+        /// Can be called by the `T::SlashCancelOrigin`.
+        ///
+        /// Parameters: era and the stashes of the validators whose slashes for that era
+        /// are killed. Stashes without a slash in that era are ignored.
+        ///
+        /// # <weight>
+        /// Same as [`Call::cancel_deferred_slash`], with S the number of stashes.
+        /// # </weight>
+        #[weight = T::DbWeight::get().reads_writes(1, 1)
+            .saturating_add(5_870 * WEIGHT_PER_MICROS)
+            .saturating_add((35 * WEIGHT_PER_MICROS).saturating_mul(stashes.len() as Weight))
+        ]
+        fn cancel_deferred_slash_for(origin, era: EraIndex, stashes: Vec<T::AccountId>) {
+            T::SlashCancelOrigin::try_origin(origin)
+                .map(|_| ())
+                .or_else(ensure_root)?;
+
+            <Self as Store>::UnappliedSlashes::mutate(&era, |unapplied|
+                unapplied.retain(|slash| !stashes.contains(&slash.validator))
+            );
+        }
+
         /// Remove all data structure concerning a staker/stash once its balance is zero.
         /// This is essentially equivalent to `withdraw_unbonded` except it can be called by anyone
-        /// and the target `stash` must have no funds left.
+        /// and the target `stash` must have no funds left, or its bonded funds must have been
+        /// slashed below the existential deposit.
         ///
         /// This can be called from any origin.
         ///
-        /// - `stash`: The stash account to reap. Its balance must be zero.
+        /// - `stash`: The stash account to reap. Its balance or its bonded total must be dust.
         ///
         /// # <weight>
         /// Complexity: O(S) where S is the number of slashing spans on the account.
@@ -1466,7 +1887,11 @@ decl_module! {
             .saturating_add(76 * WEIGHT_PER_MICROS)]
         fn reap_stash(_origin, stash: T::AccountId) {
             let at_minimum = T::Currency::total_balance(&stash) == T::Currency::minimum_balance();
-            ensure!(at_minimum, Error::<T>::FundedTarget);
+            if !at_minimum {
+                let controller = Self::bonded(&stash).ok_or(Error::<T>::NotStash)?;
+                let ledger = Self::ledger(&controller).ok_or(Error::<T>::NotController)?;
+                ensure!(ledger.total < T::Currency::minimum_balance(), Error::<T>::FundsStillBonded);
+            }
             Self::kill_stash(&stash)?;
             T::Currency::remove_lock(STAKING_ID, &stash);
         }
@@ -1495,12 +1920,71 @@ decl_module! {
             ensure_root(origin)?;
             ForceSelection::put(enable);
         }
+
+        /// Override the bonding duration, or restore `BondingDuration` with `None`.
+        ///
+        /// The new duration can't exceed `BondingDuration`, and must exceed
+        /// `SlashDeferDuration` so deferred slashes still hit the unbonding funds.
+        /// Only the chunks unbonded afterwards are affected.
+        ///
+        /// The dispatch origin must be Root.
+        #[weight = 1000]
+        fn set_bonding_duration_override(origin, maybe_duration: Option<EraIndex>) {
+            ensure_root(origin)?;
+            if let Some(duration) = maybe_duration {
+                ensure!(
+                    duration <= T::BondingDuration::get() && duration > T::SlashDeferDuration::get(),
+                    Error::<T>::InvalidBondingDuration
+                );
+            }
+            BondingDurationOverride::set(maybe_duration);
+            Self::deposit_event(RawEvent::BondingDurationSet(Self::bonding_duration()));
+        }
+
+        /// Set `HistoryDepth` value. This function will delete any history information
+        /// when `HistoryDepth` is reduced.
+        ///
+        /// Parameters:
+        /// - `new_history_depth`: The new history depth you would like to set.
+        /// - `era_items_deleted`: The number of eras expected to be pruned, only used for weight.
+        ///
+        /// The dispatch origin must be Root.
+        ///
+        /// # <weight>
+        /// - E: Number of history depths removed, i.e. 10 -> 7 = 3
+        /// - Writes: History Depth
+        /// - Clear Prefix Each: Era Stakers, EraStakersClipped, ErasValidatorPrefs, ErasAuthoringPayout,
+        ///     ErasBlocksAuthored
+        /// - Writes Each: ErasStakingPayout, ErasMarketPayout, ErasRewardPoints, ErasTotalStakes,
+        ///     ErasStartSessionIndex, ErasUnclaimedValidators
+        /// # </weight>
+        #[weight = T::DbWeight::get().reads_writes(2, 1)
+            .saturating_add(T::DbWeight::get().writes(11).saturating_mul(*era_items_deleted as Weight))]
+        fn set_history_depth(origin,
+            #[compact] new_history_depth: EraIndex,
+            #[compact] era_items_deleted: u32,
+        ) {
+            ensure_root(origin)?;
+            if let Some(current_era) = Self::current_era() {
+                let last_kept = current_era.saturating_sub(Self::history_depth());
+                let new_last_kept = current_era.saturating_sub(new_history_depth);
+                for era_index in last_kept..new_last_kept {
+                    Self::clear_era_information(era_index);
+                }
+            }
+            HistoryDepth::put(new_history_depth);
+        }
     }
 }
 
 impl<T: Config> Module<T> {
     // PUBLIC IMMUTABLES
 
+    /// The bonding duration in use, `BondingDurationOverride` if set.
+    pub fn bonding_duration() -> EraIndex {
+        Self::bonding_duration_override().unwrap_or_else(T::BondingDuration::get)
+    }
+
     /// The total balance that can be slashed from a stash account as of right now.
     pub fn slashable_balance_of(stash: &T::AccountId) -> BalanceOf<T> {
         Self::bonded(stash)
@@ -1521,7 +2005,7 @@ impl<T: Config> Module<T> {
     pub fn stage_one_stake_limit_of(own_workloads: u128) -> BalanceOf<T> {
         // we treat 1 terabytes as 1_000_000_000_000 for make `mapping_ratio = 1`
         if let Some(storage_stakes) = own_workloads.checked_mul(T::SPowerRatio::get()) {
-            storage_stakes.try_into().ok().unwrap()
+            BalanceOf::<T>::saturated_from(storage_stakes)
         } else {
             Zero::zero()
         }
@@ -1553,8 +2037,10 @@ impl<T: Config> Module<T> {
         if total_workloads_in_kb == 0 {
             Zero::zero()
         } else {
-            let workloads_to_stakes = (own_workloads_in_kb.wrapping_mul(total_stake_limit) / total_workloads_in_kb) as u128;
-            workloads_to_stakes.try_into().ok().unwrap()
+            // The product can overflow u128, saturate if the result still does
+            let workloads_to_stakes = multiply_by_rational(own_workloads_in_kb, total_stake_limit, total_workloads_in_kb)
+                .unwrap_or(u128::max_value());
+            BalanceOf::<T>::saturated_from(workloads_to_stakes)
         }
     }
 
@@ -1605,9 +2091,7 @@ impl<T: Config> Module<T> {
         let frac = frac * total_issuance;
         let integer = BalanceOf::<T>::saturated_from(integer).saturating_mul(total_issuance);
         // This value can be larger than total issuance.
-        let total_stake_limit = TryInto::<u128>::try_into(integer.saturating_add(frac))
-            .ok()
-            .unwrap();
+        let total_stake_limit = integer.saturating_add(frac).saturated_into::<u128>();
         total_stake_limit
     }
 
@@ -1644,7 +2128,7 @@ impl<T: Config> Module<T> {
             }
 
             // Fill in `new_targets`, always LOOP the `targets`
-            // However, the TC is O(1) due to the `MAX_GUARANTEE` restriction 🤪
+            // However, the TC is O(1) due to the `MaxGuarantees` restriction 🤪
             for mut target in guarantee.targets {
                 // a. Update an edge
                 if &target.who == v_stash {
@@ -1655,7 +2139,7 @@ impl<T: Config> Module<T> {
             }
 
             if !update {
-                if new_targets.len() >= MAX_GUARANTEE {
+                if new_targets.len() >= T::MaxGuarantees::get() as usize {
                     return None
                 } else {
                     // b. New an edge
@@ -1718,7 +2202,7 @@ impl<T: Config> Module<T> {
             let mut exists = false;
 
             // Always LOOP the targets
-            // However, the TC is O(1), due to the `MAX_GUARANTEE` restriction 🤪
+            // However, the TC is O(1), due to the `MaxGuarantees` restriction 🤪
             for target in guarantee.targets {
                 if &target.who == v_stash {
                     // 1. Mark it really exists (BRAVO), and update the decreased votes
@@ -1765,21 +2249,93 @@ impl<T: Config> Module<T> {
 
     /// Update the ledger for a controller. This will also update the stash lock. The lock will
     /// will lock the entire funds except paying for further transactions.
+    ///
+    /// The lock is left untouched if the total is the same as the one already locked. This
+    /// costs an extra `Ledger` read on every call, which is cheaper than the `Locks` and
+    /// `Account` writes of `set_lock` it skips.
     fn update_ledger(
         controller: &T::AccountId,
         ledger: &StakingLedger<T::AccountId, BalanceOf<T>>,
     ) {
+        if Self::ledger(controller).map(|l| l.total) != Some(ledger.total) {
+            Self::set_stash_lock(ledger);
+        }
+        <Ledger<T>>::insert(controller, ledger);
+    }
+
+    /// Lock the total of the ledger in the stash account.
+    fn set_stash_lock(ledger: &StakingLedger<T::AccountId, BalanceOf<T>>) {
         T::Currency::set_lock(
             STAKING_ID,
             &ledger.stash,
             ledger.total,
             WithdrawReasons::all(),
         );
-        <Ledger<T>>::insert(controller, ledger);
+    }
+
+    /// Withdraw the unlocked chunks of the controller's ledger, at most `maybe_value` of them
+    /// if it's given, and kill the stash if nothing is left bonded.
+    ///
+    /// Return the stash, the withdrawn amount and whether the stash was reaped.
+    fn do_withdraw_unbonded(
+        controller: &T::AccountId,
+        maybe_value: Option<BalanceOf<T>>,
+    ) -> Result<(T::AccountId, BalanceOf<T>, bool), DispatchError> {
+        let mut ledger = Self::ledger(controller).ok_or(Error::<T>::NotController)?;
+        let (stash, old_total) = (ledger.stash.clone(), ledger.total);
+        if let Some(current_era) = Self::current_era() {
+            // remove the lock first, it's set back below
+            T::Currency::remove_lock(STAKING_ID, &stash);
+            let frozen_balance = T::Currency::frozen_balance(&stash);
+            ledger = ledger.consolidate_unlocked(current_era, frozen_balance);
+
+            // put back whatever exceeds `value`, it stays unlocked but still bonded
+            let available = old_total.saturating_sub(ledger.total);
+            if let Some(value) = maybe_value.filter(|value| available > *value) {
+                let remainder = available - value;
+                ledger.total = ledger.total.saturating_add(remainder);
+                // chunks are ordered by era, the unlocked ones come first
+                let unlocked = ledger.unlocking.iter().take_while(|chunk| chunk.era <= current_era).count();
+                if unlocked > 0 {
+                    let chunk = &mut ledger.unlocking[unlocked - 1];
+                    chunk.value = chunk.value.saturating_add(remainder);
+                } else {
+                    ledger.unlocking.insert(0, UnlockChunk { value: remainder, era: current_era });
+                }
+            }
+        }
+
+        let reaped = ledger.unlocking.is_empty() && ledger.active.is_zero();
+        if reaped {
+            // This account must have called `unbond()` with some value that caused the active
+            // portion to fall below existential deposit + will have no more unlocking chunks
+            // left. We can now safely remove all staking-related information.
+            Self::kill_stash(&stash)?;
+        } else {
+            // This was the consequence of a partial unbond. just update the ledger and move on.
+            // The lock was removed above, so always set it back.
+            Self::set_stash_lock(&ledger);
+            <Ledger<T>>::insert(controller, &ledger);
+        }
+
+        // `old_total` should never be less than the new total because
+        // `consolidate_unlocked` strictly subtracts balance.
+        Ok((stash, old_total.saturating_sub(ledger.total), reaped))
     }
 
     /// Chill a stash account.
     fn chill_stash(stash: &T::AccountId) {
+        // Keep the commission as the base of this era, chilling shouldn't reset the increase limit
+        if <Validators<T>>::contains_key(stash) {
+            let current_era = Self::current_era().unwrap_or(0);
+            match Self::commission_changed_at(stash) {
+                Some((era, _)) if era == current_era => (),
+                _ => <CommissionChangedAt<T>>::insert(
+                    stash,
+                    (current_era, Perbill::one().saturating_sub(Self::validators(stash).fee))
+                ),
+            }
+        }
         <StakeLimit<T>>::remove(stash);
         <Validators<T>>::remove(stash);
         <Guarantors<T>>::remove(stash);
@@ -1836,45 +2392,66 @@ impl<T: Config> Module<T> {
             Err(pos) => ledger.claimed_rewards.insert(pos, era),
         }
         /* Input data seems good, no errors allowed after this point */
+        if <ErasStakersClipped<T>>::contains_key(&era, &ledger.stash) {
+            ErasUnclaimedValidators::mutate(&era, |count| *count = count.saturating_sub(1));
+        }
         let exposure = <ErasStakersClipped<T>>::get(&era, &ledger.stash);
         <Ledger<T>>::insert(&controller, &ledger);
 
-        // 2. Pay authoring reward
+        // 2. Calculate the total reward and guarantee rewards for staking
         let mut validator_imbalance = <PositiveImbalanceOf<T>>::zero();
-        let mut total_reward: BalanceOf<T> = Zero::zero();
-        if let Some(authoring_reward) = <ErasAuthoringPayout<T>>::get(&era, &validator_stash) {
-            total_reward = total_reward.saturating_add(authoring_reward);
-        }
-
-        let to_num =
-        |b: BalanceOf<T>| <T::CurrencyToVote as Convert<BalanceOf<T>, u128>>::convert(b);
-
-        // 3. Retrieve total stakes and total staking reward
-        let era_total_stakes = <ErasTotalStakes<T>>::get(&era);
-        let staking_reward = Perbill::from_rational_approximation(to_num(exposure.total), to_num(era_total_stakes)) * total_era_staking_payout;
-        total_reward = total_reward.saturating_add(staking_reward);
+        let (total_reward, estimated_guarantee_rewards) =
+            Self::era_reward_of(&validator_stash, era, &exposure, total_era_staking_payout);
         let total = exposure.total.max(One::one());
-        // 4. Calculate guarantee rewards for staking
-        let estimated_guarantee_rewards = <ErasValidatorPrefs<T>>::get(&era, &ledger.stash).fee * total_reward;
         let mut guarantee_rewards = Zero::zero();
-        // 5. Pay staking reward to guarantors
+        // 3. Pay staking reward to guarantors
         for i in &exposure.others {
             let reward_ratio = Perbill::from_rational_approximation(i.value, total);
             // Reward guarantors
             guarantee_rewards += reward_ratio * estimated_guarantee_rewards;
-            if let Some(imbalance) = Self::make_payout(
+            if Self::accrue_rewards(&i.who) {
+                let accrued = reward_ratio * estimated_guarantee_rewards;
+                <PendingRewards<T>>::mutate(&i.who, |pending| *pending = pending.saturating_add(accrued));
+                Self::deposit_event(RawEvent::RewardsAccrued(i.who.clone(), accrued));
+            } else if let Some(imbalance) = Self::make_payout(
                 &i.who,
                 reward_ratio * estimated_guarantee_rewards
             ) {
                 Self::deposit_event(RawEvent::Reward(i.who.clone(), imbalance.peek()));
             };
         }
-        // 6. Pay staking reward to validator
+        // 4. Pay staking reward to validator
         validator_imbalance.maybe_subsume(Self::make_payout(&ledger.stash, total_reward - guarantee_rewards));
         Self::deposit_event(RawEvent::Reward(ledger.stash, validator_imbalance.peek()));
         Ok(())
     }
 
+    /// The total reward of a validator in the era, include the authoring reward and its
+    /// staking reward, and the part of it shared by the guarantors.
+    /// return => (total_reward, estimated_guarantee_rewards)
+    fn era_reward_of(
+        validator_stash: &T::AccountId,
+        era: EraIndex,
+        exposure: &Exposure<T::AccountId, BalanceOf<T>>,
+        total_era_staking_payout: BalanceOf<T>,
+    ) -> (BalanceOf<T>, BalanceOf<T>) {
+        let to_num =
+        |b: BalanceOf<T>| <T::CurrencyToVote as Convert<BalanceOf<T>, u128>>::convert(b);
+
+        // 1. Retrieve authoring reward
+        let mut total_reward: BalanceOf<T> = <ErasAuthoringPayout<T>>::get(&era, validator_stash)
+            .unwrap_or_default();
+
+        // 2. Retrieve total stakes and total staking reward
+        let era_total_stakes = <ErasTotalStakes<T>>::get(&era);
+        let staking_reward = Perbill::from_rational_approximation(to_num(exposure.total), to_num(era_total_stakes)) * total_era_staking_payout;
+        total_reward = total_reward.saturating_add(staking_reward);
+
+        // 3. Calculate guarantee rewards for staking
+        let estimated_guarantee_rewards = <ErasValidatorPrefs<T>>::get(&era, validator_stash).fee * total_reward;
+        (total_reward, estimated_guarantee_rewards)
+    }
+
     /// Session has just ended. Provide the validator set for the next session if it's an era-end, along
     /// with the exposure of the prior validator set.
     fn new_session(
@@ -1931,6 +2508,9 @@ impl<T: Config> Module<T> {
 
         // Set staking information for new era.
         let maybe_new_validators = Self::select_and_update_validators(current_era);
+        if let Some(new_validators) = &maybe_new_validators {
+            Self::deposit_event(RawEvent::NewEra(current_era, new_validators.len() as u32));
+        }
 
         maybe_new_validators
     }
@@ -1983,7 +2563,7 @@ impl<T: Config> Module<T> {
             "💸 Start the era {:?}",
             active_era,
         );
-        let bonding_duration = T::BondingDuration::get();
+        let bonding_duration = Self::bonding_duration();
 
         BondedEras::mutate(|bonded| {
             bonded.push((active_era, start_session));
@@ -2019,6 +2599,13 @@ impl<T: Config> Module<T> {
             active_era.index,
         );
         if let Some(active_era_start) = active_era.start {
+            let points = <ErasRewardPoints<T>>::get(&active_era.index);
+            Self::deposit_event(RawEvent::EraPoints(
+                active_era.index,
+                points.total,
+                points.individual.into_iter().collect()
+            ));
+
             let now_as_millis_u64 = T::UnixTime::now().as_millis().saturated_into::<u64>();
 
             let era_duration = now_as_millis_u64 - active_era_start;
@@ -2073,7 +2660,9 @@ impl<T: Config> Module<T> {
         <ErasTotalStakes<T>>::remove(era_index);
         <ErasAuthoringPayout<T>>::remove_prefix(era_index);
         <ErasRewardPoints<T>>::remove(era_index);
+        <ErasBlocksAuthored<T>>::remove_prefix(era_index);
         ErasStartSessionIndex::remove(era_index);
+        ErasUnclaimedValidators::remove(era_index);
     }
 
     fn total_rewards_in_era(active_era: EraIndex) -> BalanceOf<T> {
@@ -2083,10 +2672,7 @@ impl<T: Config> Module<T> {
         let total_issuance = TryInto::<u128>::try_into(T::Currency::total_issuance())
             .ok()
             .unwrap();
-        // Milliseconds per year for the Julian year (365.25 days).
-        const MILLISECONDS_PER_YEAR: u64 = 1000 * 3600 * 24 * 36525 / 100;
-        // 1 Julian year = (365.25d * 24h * 3600s * 1000ms) / (millisecs_in_era = block_time * blocks_num_in_era)
-        let year_in_eras = MILLISECONDS_PER_YEAR / MILLISECS_PER_BLOCK / (EPOCH_DURATION_IN_BLOCKS * T::SessionsPerEra::get()) as u64;
+        let year_in_eras = Self::year_in_eras();
         let year_num = active_era.saturating_sub(Self::start_reward_era()) as u64 / year_in_eras;
         for _ in 0..year_num {
             maybe_rewards_this_year = maybe_rewards_this_year * REWARD_DECREASE_RATIO.0 / REWARD_DECREASE_RATIO.1;
@@ -2108,6 +2694,262 @@ impl<T: Config> Module<T> {
         reward_this_era.try_into().ok().unwrap()
     }
 
+    fn year_in_eras() -> u64 {
+        // Milliseconds per year for the Julian year (365.25 days).
+        const MILLISECONDS_PER_YEAR: u64 = 1000 * 3600 * 24 * 36525 / 100;
+        // 1 Julian year = (365.25d * 24h * 3600s * 1000ms) / (millisecs_in_era = block_time * blocks_num_in_era)
+        MILLISECONDS_PER_YEAR / MILLISECS_PER_BLOCK / (EPOCH_DURATION_IN_BLOCKS * T::SessionsPerEra::get()) as u64
+    }
+
+    /// Get the staking ratio of the active era, i.e. its total stakes over the total issuance,
+    /// and the annual inflation, i.e. the era's rewards of the yearly reward schedule
+    /// (including the extra rewards after `EXTRA_REWARD_START_YEAR`) times the eras in a
+    /// year, over the total issuance. The inflation doesn't depend on the staking ratio.
+    pub fn inflation_info() -> (Perbill, Perbill) {
+        let to_num =
+            |b: BalanceOf<T>| <T::CurrencyToVote as Convert<BalanceOf<T>, u128>>::convert(b);
+        let total_issuance = to_num(T::Currency::total_issuance());
+        let active_era = Self::active_era().map(|e| e.index).unwrap_or(0);
+
+        let staking_ratio = Perbill::from_rational_approximation(
+            to_num(<ErasTotalStakes<T>>::get(&active_era)),
+            total_issuance
+        );
+        let rewards_this_year = to_num(Self::total_rewards_in_era(active_era))
+            .saturating_mul(Self::year_in_eras() as u128);
+        let inflation = Perbill::from_rational_approximation(rewards_this_year, total_issuance);
+
+        (staking_ratio, inflation)
+    }
+
+    /// Get the claim status of the given era's rewards.
+    pub fn era_payout_status(era: EraIndex) -> EraPayoutStatus {
+        let current_era = Self::current_era().unwrap_or(0);
+        if era < current_era.saturating_sub(Self::history_depth()) {
+            EraPayoutStatus::Pruned
+        } else if !<ErasStakingPayout<T>>::contains_key(&era) {
+            EraPayoutStatus::NotComputed
+        } else if !ErasUnclaimedValidators::contains_key(&era) {
+            // Eras before the counter was introduced, some rewards may still be claimable
+            EraPayoutStatus::Claimable
+        } else if Self::eras_unclaimed_validators(&era) == 0 {
+            EraPayoutStatus::FullyClaimed
+        } else {
+            EraPayoutStatus::Claimable
+        }
+    }
+
+    /// Get the stake of the guarantor backing validators which have pending unapplied
+    /// slashes, according to the exposures of the active era.
+    pub fn at_risk_stake(guarantor: &T::AccountId) -> BalanceOf<T> {
+        let active_era = match Self::active_era() {
+            Some(active_era) => active_era.index,
+            None => return Zero::zero(),
+        };
+        let earliest = <Self as Store>::EarliestUnappliedSlash::get().unwrap_or(active_era);
+
+        let mut slashed_validators: Vec<T::AccountId> = vec![];
+        for era in earliest..=active_era {
+            for slash in <Self as Store>::UnappliedSlashes::get(&era) {
+                if !slashed_validators.contains(&slash.validator) {
+                    slashed_validators.push(slash.validator);
+                }
+            }
+        }
+
+        slashed_validators.iter().fold(Zero::zero(), |acc: BalanceOf<T>, v_stash| {
+            Self::eras_stakers(active_era, v_stash).others.iter()
+                .filter(|ie| &ie.who == guarantor)
+                .fold(acc, |acc, ie| acc.saturating_add(ie.value))
+        })
+    }
+
+    /// Get the reward points of the active era, as the total and the points of each validator.
+    pub fn current_era_points() -> (RewardPoint, Vec<(T::AccountId, RewardPoint)>) {
+        let active_era = match Self::active_era() {
+            Some(active_era) => active_era.index,
+            None => return (0, vec![]),
+        };
+        let points = <ErasRewardPoints<T>>::get(&active_era);
+        (points.total, points.individual.into_iter().collect())
+    }
+
+    /// Get the deferred slashes reported in the era, in the order used by
+    /// [`Call::cancel_deferred_slash`].
+    pub fn deferred_slashes_of_era(era: EraIndex) -> Vec<UnappliedSlash<T::AccountId, BalanceOf<T>>> {
+        <Self as Store>::UnappliedSlashes::get(&era)
+    }
+
+    /// Get the deferred slashes not yet applied to the stash, as a validator or a guarantor,
+    /// grouped by the era they were reported in.
+    pub fn pending_slashes_of(stash: &T::AccountId) -> Vec<(EraIndex, BalanceOf<T>)> {
+        let active_era = match Self::active_era() {
+            Some(active_era) => active_era.index,
+            None => return vec![],
+        };
+        let earliest = <Self as Store>::EarliestUnappliedSlash::get().unwrap_or(active_era);
+
+        let mut pending = vec![];
+        for era in earliest..=active_era {
+            let amount = <Self as Store>::UnappliedSlashes::get(&era).iter()
+                .fold(Zero::zero(), |acc: BalanceOf<T>, slash| {
+                    let own = if &slash.validator == stash { slash.own } else { Zero::zero() };
+                    slash.others.iter()
+                        .filter(|(who, _)| who == stash)
+                        .fold(acc.saturating_add(own), |acc, (_, value)| acc.saturating_add(*value))
+                });
+            if !amount.is_zero() {
+                pending.push((era, amount));
+            }
+        }
+        pending
+    }
+
+    /// Get the rewards of the account, as a validator or a guarantor, for each era within
+    /// `[from_era, to_era]` whose payout is computed, the eras out of `HistoryDepth` are skipped.
+    ///
+    /// This iterates all the clipped exposures of every era, so it's only meant for RPC.
+    pub fn reward_history(who: &T::AccountId, from_era: EraIndex, to_era: EraIndex) -> Vec<(EraIndex, BalanceOf<T>)> {
+        let current_era = match Self::current_era() {
+            Some(current_era) => current_era,
+            None => return vec![],
+        };
+        let from_era = from_era.max(current_era.saturating_sub(Self::history_depth()));
+        let to_era = to_era.min(current_era);
+
+        (from_era..=to_era).filter_map(|era| {
+            let total_era_staking_payout = <ErasStakingPayout<T>>::get(&era)?;
+            let mut reward: BalanceOf<T> = Zero::zero();
+            for (v_stash, exposure) in <ErasStakersClipped<T>>::iter_prefix(&era) {
+                let (total_reward, estimated_guarantee_rewards) =
+                    Self::era_reward_of(&v_stash, era, &exposure, total_era_staking_payout);
+                let total = exposure.total.max(One::one());
+                let mut guarantee_rewards: BalanceOf<T> = Zero::zero();
+                for ie in &exposure.others {
+                    let g_reward = Perbill::from_rational_approximation(ie.value, total) * estimated_guarantee_rewards;
+                    guarantee_rewards = guarantee_rewards.saturating_add(g_reward);
+                    if &ie.who == who {
+                        reward = reward.saturating_add(g_reward);
+                    }
+                }
+                if &v_stash == who {
+                    reward = reward.saturating_add(total_reward.saturating_sub(guarantee_rewards));
+                }
+            }
+            if reward.is_zero() { None } else { Some((era, reward)) }
+        }).collect()
+    }
+
+    /// Get the stake limit a validator would get with `own_workload` (in bytes), under the
+    /// current total issuance and the total workload of the last `report_works`.
+    pub fn stake_limit_of_workload(own_workload: u128) -> BalanceOf<T> {
+        let total_workload = Self::total_workload();
+        let total_stake_limit = Self::calculate_total_stake_limit();
+        if total_workload.saturating_mul(T::SPowerRatio::get()) < total_stake_limit {
+            Self::stage_one_stake_limit_of(own_workload)
+        } else {
+            Self::stage_two_stake_limit_of(own_workload / 1024, total_workload / 1024, total_stake_limit)
+        }
+    }
+
+    /// Get the workload (in bytes) a validator needs to reach `target_stake` as its stake limit,
+    /// this is the inverse of `stage_one_stake_limit_of` and `stage_two_stake_limit_of` under the
+    /// current total issuance and the total workload of the last `report_works`.
+    ///
+    /// Returns `u128::MAX` if no workload is reported yet or no workload can reach the target.
+    pub fn workload_for_stake(target_stake: BalanceOf<T>) -> u128 {
+        let total_workload = Self::total_workload();
+        if total_workload == 0 {
+            return u128::MAX;
+        }
+        let target_stake = TryInto::<u128>::try_into(target_stake).ok().unwrap_or(u128::MAX);
+        let div_ceil = |a: u128, b: u128| {
+            if b == 0 { u128::MAX } else { a / b + if a % b == 0 { 0 } else { 1 } }
+        };
+        let total_stake_limit = Self::calculate_total_stake_limit();
+        if total_workload.saturating_mul(T::SPowerRatio::get()) < total_stake_limit {
+            // Stage one: stake limit = own_workload * SPowerRatio
+            div_ceil(target_stake, T::SPowerRatio::get())
+        } else {
+            // Stage two: stake limit = own_workload_in_kb * total_stake_limit / total_workload_in_kb
+            let own_workload_in_kb = div_ceil(target_stake.saturating_mul(total_workload / 1024), total_stake_limit);
+            if total_workload / 1024 == 0 { u128::MAX } else { own_workload_in_kb.saturating_mul(1024) }
+        }
+    }
+
+    /// Get how much more stake the validator can take under its stake limit,
+    /// according to its exposure of the active era.
+    pub fn validator_headroom(validator: &T::AccountId) -> BalanceOf<T> {
+        let limit = Self::stake_limit(validator).unwrap_or_default();
+        let exposed = Self::active_era()
+            .map(|active_era| Self::eras_stakers(active_era.index, validator).total)
+            .unwrap_or_default();
+        limit.saturating_sub(exposed)
+    }
+
+    /// Get the validators whose exposure of the active era includes the guarantor,
+    /// with the stake actually backing each of them.
+    ///
+    /// This iterates all the exposures of the active era, so it's only meant for RPC.
+    pub fn active_backing(guarantor: &T::AccountId) -> Vec<(T::AccountId, BalanceOf<T>)> {
+        let active_era = match Self::active_era() {
+            Some(active_era) => active_era.index,
+            None => return vec![],
+        };
+        <ErasStakers<T>>::iter_prefix(&active_era).filter_map(|(v_stash, exposure)| {
+            exposure.others.iter()
+                .find(|ie| &ie.who == guarantor)
+                .map(|ie| (v_stash, ie.value))
+        }).collect()
+    }
+
+    /// Get the elected validators of the current era, with their exposure and preference.
+    /// A validator without exposure is returned with zero stakes.
+    pub fn current_validator_set() -> Vec<ValidatorInfo<T::AccountId, BalanceOf<T>>> {
+        let current_era = Self::current_era().unwrap_or(0);
+        Self::current_elected().into_iter().map(|v_stash| {
+            let exposure = Self::eras_stakers(current_era, &v_stash);
+            let prefs = Self::eras_validator_prefs(current_era, &v_stash);
+            ValidatorInfo {
+                own: exposure.own,
+                total: exposure.total,
+                guarantors_count: exposure.others.len() as u32,
+                fee: prefs.fee,
+                stash: v_stash,
+            }
+        }).collect()
+    }
+
+    /// Get the slashing records of the validator: the still-bonded eras it was slashed in,
+    /// its slashing spans and whether a slash of it is waiting to be applied.
+    pub fn validator_risk(validator: &T::AccountId) -> ValidatorRisk {
+        let recent_offences = <Self as Store>::BondedEras::get().iter()
+            .filter(|(era, _)| <Self as Store>::ValidatorSlashInEra::contains_key(era, validator))
+            .count() as u32;
+        let slashing_spans = <Self as Store>::SlashingSpans::get(validator)
+            .map_or(0, |spans| spans.iter().count() as u32);
+        let pending_slash = Self::active_era().map_or(false, |active_era| {
+            let earliest = <Self as Store>::EarliestUnappliedSlash::get().unwrap_or(active_era.index);
+            (earliest..=active_era.index).any(|era|
+                <Self as Store>::UnappliedSlashes::get(&era).iter().any(|slash| &slash.validator == validator)
+            )
+        });
+
+        ValidatorRisk { recent_offences, slashing_spans, pending_slash }
+    }
+
+    /// Get the number of guarantors grouped by how many targets they guarantee,
+    /// as `(targets count, guarantors count)` sorted by the targets count.
+    ///
+    /// This iterates all the guarantors, so it's only meant for RPC.
+    pub fn guarantee_target_histogram() -> Vec<(u32, u32)> {
+        let mut histogram: BTreeMap<u32, u32> = BTreeMap::new();
+        for (_, guarantee) in <Guarantors<T>>::iter() {
+            *histogram.entry(guarantee.targets.len() as u32).or_insert(0) += 1;
+        }
+        histogram.into_iter().collect()
+    }
+
     fn supply_extra_rewards_due_to_low_effective_staking_ratio(total_issuance: u128) -> u128 {
         let maybe_effective_staking_ratio = Self::maybe_get_effective_staking_ratio(BalanceOf::<T>::saturated_from(total_issuance));
         if let Some(effective_staking_ratio) = maybe_effective_staking_ratio {
@@ -2145,7 +2987,11 @@ impl<T: Config> Module<T> {
             for era in (*earliest)..keep_from {
                 let era_slashes = <Self as Store>::UnappliedSlashes::take(&era);
                 for slash in era_slashes {
+                    let validator = slash.validator.clone();
+                    let slashed = slash.others.iter()
+                        .fold(slash.own, |acc, (_, value)| acc.saturating_add(*value));
                     slashing::apply_slash::<T>(slash);
+                    Self::deposit_event(RawEvent::DeferredSlashApplied(era, validator, slashed));
                 }
             }
 
@@ -2166,11 +3012,8 @@ impl<T: Config> Module<T> {
         if validator_count < minimum_validator_count {
             // There were not enough validators for even our minimal level of functionality.
             // This is bad🥺.
-            // We should probably disable all functionality except for block production
-            // and let the chain keep producing blocks until we can decide on a sufficiently
-            // substantial set.
-            // TODO: [Substrate]substrate#2494
-            return None
+            // The runtime decides how to degrade through `Fallback`.
+            return Self::election_fallback(current_era, validator_count, minimum_validator_count);
         }
 
         let to_votes =
@@ -2225,24 +3068,44 @@ impl<T: Config> Module<T> {
         let mut eras_total_stakes: BalanceOf<T> = Zero::zero();
         let mut validators_stakes: Vec<(T::AccountId, u128)> = vec![];
         for (v_stash, voters) in vg_graph.iter() {
-            let v_controller = Self::bonded(v_stash).unwrap();
+            // The stash may have been reaped or unbonded since it declared to validate
             let v_ledger: StakingLedger<T::AccountId, BalanceOf<T>> =
-                Self::ledger(&v_controller).unwrap();
+                match Self::bonded(v_stash).and_then(|v_controller| Self::ledger(&v_controller)) {
+                    Some(v_ledger) => v_ledger,
+                    None => {
+                        log!(
+                            warn,
+                            "💸 Validator {:?} has no ledger, skip it in the era {:?}.",
+                            v_stash,
+                            current_era,
+                        );
+                        Self::deposit_event(RawEvent::ValidatorExcludedNoLedger(v_stash.clone()));
+                        continue;
+                    }
+                };
 
             let stake_limit = Self::stake_limit(v_stash).unwrap_or(Zero::zero());
+            let total_stakes = v_ledger.active.saturating_add(
+                voters.iter().fold(
+                    Zero::zero(),
+                    |acc, ie| acc.saturating_add(ie.value)
+                ));
 
             // 0. Add to `validator_stakes` but skip adding to `eras_stakers` if stake limit goes 0
             if stake_limit == Zero::zero() {
+                if !total_stakes.is_zero() {
+                    Self::deposit_event(RawEvent::StakeLimitReduced(v_stash.clone(), total_stakes, Zero::zero()));
+                }
+                for voter in voters {
+                    Self::deposit_event(RawEvent::GuarantorStakeReduced(
+                        voter.who.clone(), v_stash.clone(), voter.value, Zero::zero()
+                    ));
+                }
                 validators_stakes.push((v_stash.clone(), 0));
                 continue;
             }
 
             // 1. Calculate the ratio
-            let total_stakes = v_ledger.active.saturating_add(
-                voters.iter().fold(
-                    Zero::zero(),
-                    |acc, ie| acc.saturating_add(ie.value)
-                ));
             let valid_votes_ratio = Perbill::from_rational_approximation(stake_limit, total_stakes).min(Perbill::one());
 
             // 2. Calculate validator valid stake
@@ -2256,14 +3119,49 @@ impl<T: Config> Module<T> {
             };
             for voter in voters {
                 let g_valid_stake = valid_votes_ratio * voter.value;
+                if g_valid_stake < voter.value {
+                    Self::deposit_event(RawEvent::GuarantorStakeReduced(
+                        voter.who.clone(), v_stash.clone(), voter.value, g_valid_stake
+                    ));
+                }
                 new_exposure.total = new_exposure.total.saturating_add(g_valid_stake);
                 new_exposure.others.push(IndividualExposure {
                     who: voter.who.clone(),
                     value: g_valid_stake
                 });
             }
+            if new_exposure.total < total_stakes {
+                Self::deposit_event(RawEvent::StakeLimitReduced(v_stash.clone(), total_stakes, new_exposure.total));
+            }
 
-            // 4. Update snapshots
+            // 4. Trim the smallest guarantors' stakes if the exposure exceeds the cap
+            if let Some(cap) = T::MaxStakePerValidator::get() {
+                if new_exposure.total > cap {
+                    let mut excess = new_exposure.total - cap;
+                    new_exposure.others.sort_by(|a, b| a.value.cmp(&b.value));
+                    for ie in new_exposure.others.iter_mut() {
+                        if excess.is_zero() { break; }
+                        let cut = ie.value.min(excess);
+                        Self::deposit_event(RawEvent::GuarantorStakeReduced(
+                            ie.who.clone(), v_stash.clone(), ie.value, ie.value - cut
+                        ));
+                        ie.value -= cut;
+                        excess -= cut;
+                    }
+                    new_exposure.others.retain(|ie| !ie.value.is_zero());
+                    let trimmed = new_exposure.total - cap - excess;
+                    new_exposure.total -= trimmed;
+                    Self::deposit_event(RawEvent::StakeCappedForValidator(v_stash.clone(), trimmed));
+                }
+            }
+
+            // 5. Exclude the validator if its own stake is too small
+            if new_exposure.own < T::MinSelfStakeRatio::get() * new_exposure.total {
+                Self::deposit_event(RawEvent::ValidatorExcludedLowSelfStake(v_stash.clone()));
+                continue;
+            }
+
+            // 6. Update snapshots
             <ErasStakers<T>>::insert(&current_era, &v_stash, new_exposure.clone());
             let exposure_total = new_exposure.total;
             let mut exposure_clipped = new_exposure;
@@ -2273,6 +3171,7 @@ impl<T: Config> Module<T> {
                 exposure_clipped.others.truncate(clipped_max_len);
             }
             <ErasStakersClipped<T>>::insert(&current_era, &v_stash, exposure_clipped);
+            ErasUnclaimedValidators::mutate(&current_era, |count| *count = count.saturating_add(1));
 
             <ErasValidatorPrefs<T>>::insert(&current_era, &v_stash, Self::validators(&v_stash).clone());
             if let Some(maybe_total_stakes) = eras_total_stakes.checked_add(&exposure_total) {
@@ -2281,7 +3180,7 @@ impl<T: Config> Module<T> {
                 eras_total_stakes = to_balance(u64::max_value() as u128);
             }
 
-            // 5. Push validator stakes
+            // 7. Push validator stakes
             validators_stakes.push((v_stash.clone(), to_votes(exposure_total)))
         }
 
@@ -2296,7 +3195,7 @@ impl<T: Config> Module<T> {
 
         // If there's no validators, be as same as little validators
         if to_elect < minimum_validator_count {
-            return None;
+            return Self::election_fallback(current_era, to_elect, minimum_validator_count);
         }
 
         let elected_stashes= Self::do_election(validators_stakes, to_elect);
@@ -2321,6 +3220,64 @@ impl<T: Config> Module<T> {
         Some(elected_stashes)
     }
 
+    /// Handle the election of `current_era` which doesn't have enough candidates
+    /// according to `Fallback`.
+    fn election_fallback(current_era: EraIndex, found: usize, needed: usize) -> Option<Vec<T::AccountId>> {
+        log!(
+            warn,
+            "💸 only {:?} candidates for era {:?}, {:?} needed",
+            found,
+            current_era,
+            needed,
+        );
+        Self::deposit_event(RawEvent::InsufficientCandidates(found as u32, needed as u32));
+
+        match T::Fallback::get() {
+            FallbackMode::StallElection => None,
+            FallbackMode::KeepCurrentSet => {
+                let prev_era = current_era.checked_sub(1)?;
+                // Only the kept set is copied, so the copy is bounded by the validator count
+                let elected_stashes: Vec<T::AccountId> = Self::current_elected()
+                    .into_iter()
+                    .take(Self::validator_count() as usize)
+                    .collect();
+                if elected_stashes.is_empty() {
+                    return None;
+                }
+
+                // Replace whatever has been built for this era with the previous era's
+                <ErasStakers<T>>::remove_prefix(&current_era);
+                <ErasStakersClipped<T>>::remove_prefix(&current_era);
+                <ErasValidatorPrefs<T>>::remove_prefix(&current_era);
+                let mut unclaimed_validators: u32 = 0;
+                let mut total_stakes: BalanceOf<T> = Zero::zero();
+                for v_stash in elected_stashes.iter() {
+                    // The previous era may have stalled, then there is nothing to keep
+                    if !<ErasStakersClipped<T>>::contains_key(&prev_era, v_stash) {
+                        continue;
+                    }
+                    let exposure = <ErasStakers<T>>::get(&prev_era, v_stash);
+                    total_stakes = total_stakes.saturating_add(exposure.total);
+                    <ErasStakers<T>>::insert(&current_era, v_stash, exposure);
+                    <ErasStakersClipped<T>>::insert(&current_era, v_stash, <ErasStakersClipped<T>>::get(&prev_era, v_stash));
+                    <ErasValidatorPrefs<T>>::insert(&current_era, v_stash, <ErasValidatorPrefs<T>>::get(&prev_era, v_stash));
+                    unclaimed_validators = unclaimed_validators.saturating_add(1);
+                }
+                ErasUnclaimedValidators::insert(&current_era, unclaimed_validators);
+                <ErasTotalStakes<T>>::insert(&current_era, total_stakes);
+
+                // Era rotation has no weight of its own, register the copy
+                let copied = elected_stashes.len() as Weight;
+                <frame_system::Module<T>>::register_extra_weight_unchecked(
+                    T::DbWeight::get().reads_writes(4 * copied + 2, 3 * copied + 5),
+                    DispatchClass::Mandatory,
+                );
+
+                Some(elected_stashes)
+            }
+        }
+    }
+
     /// Remove all associated data of a stash account from the staking system.
     ///
     /// Assumes storage is upgraded before calling.
@@ -2328,9 +3285,14 @@ impl<T: Config> Module<T> {
     /// This is called :
     /// - Immediately when an account's balance falls below existential deposit.
     /// - after a `withdraw_unbond()` call that frees all of a stash's bonded balance.
+    ///
+    /// Accrued rewards are paid out first and emit `RewardsClaimed`.
     fn kill_stash(stash: &T::AccountId) -> DispatchResult {
         let controller = <Bonded<T>>::get(stash).ok_or(Error::<T>::NotStash)?;
 
+        // Pay out the accrued rewards while the payee is still known
+        Self::pay_accrued_before_kill(stash);
+
         <Bonded<T>>::remove(stash);
         <Ledger<T>>::remove(&controller);
 
@@ -2338,6 +3300,9 @@ impl<T: Config> Module<T> {
         <Validators<T>>::remove(stash);
         <Guarantors<T>>::remove(stash);
         <StakeLimit<T>>::remove(stash);
+        <BondedAt<T>>::remove(stash);
+        <CommissionChangedAt<T>>::remove(stash);
+        <AccrueRewards<T>>::remove(stash);
 
         // TODO: this may update with `num_slashing_spans`?
         slashing::clear_stash_metadata::<T>(stash);
@@ -2345,6 +3310,41 @@ impl<T: Config> Module<T> {
         Ok(())
     }
 
+    /// Pay the accrued rewards of a stash which is going to be killed, like `claim_accrued`.
+    ///
+    /// Nothing stays bonded, so `Staked` rewards are paid to the stash. If the payee can't
+    /// receive them, they are paid to the stash instead. Killing never fails because of
+    /// this, the callers may have removed the lock already.
+    fn pay_accrued_before_kill(stash: &T::AccountId) {
+        let pending = <PendingRewards<T>>::take(stash);
+        if pending.is_zero() {
+            return;
+        }
+
+        let maybe_paid = match Self::payee(stash) {
+            RewardDestination::Staked => None,
+            _ => Self::make_payout(stash, pending),
+        };
+        let paid = maybe_paid.unwrap_or_else(|| T::Currency::deposit_creating(stash, pending));
+        if paid.peek() < pending {
+            log!(
+                warn,
+                "💸 Accrued rewards {:?} of {:?} can't be paid in full, {:?} is paid.",
+                pending,
+                stash,
+                paid.peek(),
+            );
+        }
+        Self::deposit_event(RawEvent::RewardsClaimed(stash.clone(), paid.peek()));
+    }
+
+    /// Whether the stash was bonded less than `SlashingImmunityEras` before `era`.
+    fn is_slashing_immune(stash: &T::AccountId, era: EraIndex) -> bool {
+        Self::bonded_at(stash).map_or(false, |bonded_era|
+            era < bonded_era.saturating_add(T::SlashingImmunityEras::get())
+        )
+    }
+
     /// Add reward points to validators using their stash account ID.
     ///
     /// Validators are keyed by stash account ID and must be in the current elected set.
@@ -2498,25 +3498,32 @@ impl<T: Config> swork::Works<T::AccountId> for Module<T> {
             Self::update_stage_two_stake_limit(workload_map, total_workload, total_stake_limit)
         };
         add_db_reads_writes(validators_count, validators_count);
+        TotalWorkload::put(total_workload);
+        add_db_reads_writes(0, 1);
         Self::deposit_event(RawEvent::UpdateStakeLimitSuccess(group_counts));
         consumed_weight
     }
 }
 
-/// Add reward points to block authors:
-/// * 20 points to the block producer for producing a (non-uncle) block in the relay chain,
-/// * 2 points to the block producer for each reference to a previously unreferenced uncle, and
-/// * 1 point to the producer of each referenced uncle block.
+/// Add reward points to block authors, according to `T::AuthoringPoints`:
+/// * points to the block producer for producing a (non-uncle) block in the relay chain,
+/// * points to the block producer for each reference to a previously unreferenced uncle, and
+/// * points to the producer of each referenced uncle block.
 impl<T: Config + pallet_authorship::Config>
     pallet_authorship::EventHandler<T::AccountId, T::BlockNumber> for Module<T>
 {
     fn note_author(author: T::AccountId) {
-        Self::reward_by_ids(vec![(author, 20)]);
+        let (block_points, _, _) = T::AuthoringPoints::get();
+        if let Some(active_era) = Self::active_era() {
+            <ErasBlocksAuthored<T>>::mutate(active_era.index, &author, |count| *count = count.saturating_add(1));
+        }
+        Self::reward_by_ids(vec![(author, block_points)]);
     }
     fn note_uncle(author: T::AccountId, _age: T::BlockNumber) {
+        let (_, uncle_ref_points, uncle_points) = T::AuthoringPoints::get();
         Self::reward_by_ids(vec![
-            (<pallet_authorship::Module<T>>::author(), 2),
-            (author, 1),
+            (<pallet_authorship::Module<T>>::author(), uncle_ref_points),
+            (author, uncle_points),
         ])
     }
 }
@@ -2594,7 +3601,7 @@ for Module<T> where
             });
         add_db_reads_writes(1, 0);
 
-        let window_start = active_era.saturating_sub(T::BondingDuration::get());
+        let window_start = active_era.saturating_sub(Self::bonding_duration());
 
         // fast path for active-era report - most likely.
         // `slash_session` cannot be in a future active era. It must be in `active_era` or before.
@@ -2632,6 +3639,13 @@ for Module<T> where
                 continue
             }
 
+            // Skip if the offence happened within the stash's immunity window.
+            if Self::is_slashing_immune(stash, slash_era) {
+                add_db_reads_writes(1, 0);
+                Self::deposit_event(RawEvent::OffenceSkippedImmunity(stash.clone()));
+                continue
+            }
+
             let unapplied = slashing::compute_slash::<T>(slashing::SlashParams {
                 stash,
                 slash: *slash_fraction,
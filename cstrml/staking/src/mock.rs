@@ -56,6 +56,16 @@ thread_local! {
     static SESSION: RefCell<(Vec<AccountId>, HashSet<AccountId>)> = RefCell::new(Default::default());
     static EXISTENTIAL_DEPOSIT: RefCell<u128> = RefCell::new(0);
     static SLASH_DEFER_DURATION: RefCell<EraIndex> = RefCell::new(0);
+    static SLASHING_IMMUNITY_ERAS: RefCell<EraIndex> = RefCell::new(0);
+    static AUTHORING_POINTS: RefCell<(u32, u32, u32)> = RefCell::new((20, 2, 1));
+    static MAX_STAKE_PER_VALIDATOR: RefCell<Option<Balance>> = RefCell::new(None);
+    static MIN_SELF_STAKE_RATIO: RefCell<Perbill> = RefCell::new(Perbill::zero());
+    static MAX_COMMISSION_INCREASE_PER_ERA: RefCell<Perbill> = RefCell::new(Perbill::one());
+    static FALLBACK: RefCell<FallbackMode> = RefCell::new(FallbackMode::StallElection);
+    static MAX_GUARANTEES: RefCell<u32> = RefCell::new(16);
+    static MAX_COMMISSION: RefCell<Perbill> = RefCell::new(Perbill::one());
+    static MIN_VALIDATOR_BOND: RefCell<Balance> = RefCell::new(0);
+    static MIN_GUARANTOR_BOND: RefCell<Balance> = RefCell::new(0);
     static OWN_WORKLOAD: RefCell<u128> = RefCell::new(0);
     static TOTAL_WORKLOAD: RefCell<u128> = RefCell::new(0);
     static DSM_STAKING_PAYOUT: RefCell<Balance> = RefCell::new(0);
@@ -113,6 +123,76 @@ impl Get<EraIndex> for SlashDeferDuration {
     }
 }
 
+pub struct SlashingImmunityEras;
+impl Get<EraIndex> for SlashingImmunityEras {
+    fn get() -> EraIndex {
+        SLASHING_IMMUNITY_ERAS.with(|v| *v.borrow())
+    }
+}
+
+pub struct AuthoringPoints;
+impl Get<(u32, u32, u32)> for AuthoringPoints {
+    fn get() -> (u32, u32, u32) {
+        AUTHORING_POINTS.with(|v| *v.borrow())
+    }
+}
+
+pub struct MaxStakePerValidator;
+impl Get<Option<Balance>> for MaxStakePerValidator {
+    fn get() -> Option<Balance> {
+        MAX_STAKE_PER_VALIDATOR.with(|v| *v.borrow())
+    }
+}
+
+pub struct MinSelfStakeRatio;
+impl Get<Perbill> for MinSelfStakeRatio {
+    fn get() -> Perbill {
+        MIN_SELF_STAKE_RATIO.with(|v| *v.borrow())
+    }
+}
+
+pub struct MaxCommissionIncreasePerEra;
+impl Get<Perbill> for MaxCommissionIncreasePerEra {
+    fn get() -> Perbill {
+        MAX_COMMISSION_INCREASE_PER_ERA.with(|v| *v.borrow())
+    }
+}
+
+pub struct Fallback;
+impl Get<FallbackMode> for Fallback {
+    fn get() -> FallbackMode {
+        FALLBACK.with(|v| *v.borrow())
+    }
+}
+
+pub struct MaxGuarantees;
+impl Get<u32> for MaxGuarantees {
+    fn get() -> u32 {
+        MAX_GUARANTEES.with(|v| *v.borrow())
+    }
+}
+
+pub struct MaxCommission;
+impl Get<Perbill> for MaxCommission {
+    fn get() -> Perbill {
+        MAX_COMMISSION.with(|v| *v.borrow())
+    }
+}
+
+pub struct MinValidatorBond;
+impl Get<Balance> for MinValidatorBond {
+    fn get() -> Balance {
+        MIN_VALIDATOR_BOND.with(|v| *v.borrow())
+    }
+}
+
+pub struct MinGuarantorBond;
+impl Get<Balance> for MinGuarantorBond {
+    fn get() -> Balance {
+        MIN_GUARANTOR_BOND.with(|v| *v.borrow())
+    }
+}
+
 /// Author of block is always 11
 pub struct Author11;
 impl FindAuthor<u128> for Author11 {
@@ -140,7 +220,7 @@ impl frame_system::Config for Test {
     type AccountId = AccountId;
     type Lookup = IdentityLookup<Self::AccountId>;
     type Header = Header;
-    type Event = ();
+    type Event = Event;
     type BlockHashCount = BlockHashCount;
     type DbWeight = RocksDbWeight;
     type Version = ();
@@ -160,7 +240,7 @@ parameter_types! {
 impl balances::Config for Test {
     type Balance = Balance;
     type DustRemoval = ();
-    type Event = ();
+    type Event = Event;
     type ExistentialDeposit = ExistentialDeposit;
     type AccountStore = System;
     type WeightInfo = ();
@@ -178,7 +258,7 @@ sp_runtime::impl_opaque_keys! {
 	}
 }
 impl pallet_session::Config for Test {
-    type Event = ();
+    type Event = Event;
     type ValidatorId = AccountId;
     type ValidatorIdOf = crate::StashOf<Test>;
     type ShouldEndSession = pallet_session::PeriodicSessions<Period, Offset>;
@@ -267,7 +347,7 @@ parameter_types! {
 
 impl swork::Config for Test {
     type Currency = Balances;
-    type Event = ();
+    type Event = Event;
     type PunishmentSlots = PunishmentSlots;
     type Works = TestStaking;
     type MarketInterface = TestStaking;
@@ -292,7 +372,7 @@ impl Config for Test {
     type UnixTime = Timestamp;
     type CurrencyToVote = CurrencyToVoteHandler;
     type RewardRemainder = ();
-    type Event = ();
+    type Event = Event;
     type Slash = ();
     type Reward = ();
     type Randomness = TestRandomness;
@@ -307,6 +387,16 @@ impl Config for Test {
     type MarketStakingPotDuration = MarketStakingPotDuration;
     type BenefitInterface = TestBenefitInterface;
     type UncheckedFrozenBondFund = UncheckedFrozenBondFund;
+    type SlashingImmunityEras = SlashingImmunityEras;
+    type AuthoringPoints = AuthoringPoints;
+    type MaxStakePerValidator = MaxStakePerValidator;
+    type MinSelfStakeRatio = MinSelfStakeRatio;
+    type MaxCommissionIncreasePerEra = MaxCommissionIncreasePerEra;
+    type Fallback = Fallback;
+    type MaxGuarantees = MaxGuarantees;
+    type MaxCommission = MaxCommission;
+    type MinValidatorBond = MinValidatorBond;
+    type MinGuarantorBond = MinGuarantorBond;
     type WeightInfo = weight::WeightInfo;
 }
 
@@ -335,6 +425,16 @@ pub struct ExtBuilder {
     validator_count: u32,
     minimum_validator_count: u32,
     slash_defer_duration: EraIndex,
+    slashing_immunity_eras: EraIndex,
+    authoring_points: (u32, u32, u32),
+    max_stake_per_validator: Option<Balance>,
+    min_self_stake_ratio: Perbill,
+    max_commission_increase_per_era: Perbill,
+    fallback: FallbackMode,
+    max_guarantees: u32,
+    max_commission: Perbill,
+    min_validator_bond: Balance,
+    min_guarantor_bond: Balance,
     fair: bool,
     num_validators: Option<u32>,
     invulnerables: Vec<u128>,
@@ -354,6 +454,16 @@ impl Default for ExtBuilder {
             validator_count: 2,
             minimum_validator_count: 0,
             slash_defer_duration: 0,
+            slashing_immunity_eras: 0,
+            authoring_points: (20, 2, 1),
+            max_stake_per_validator: None,
+            min_self_stake_ratio: Perbill::zero(),
+            max_commission_increase_per_era: Perbill::one(),
+            fallback: FallbackMode::StallElection,
+            max_guarantees: 16,
+            max_commission: Perbill::one(),
+            min_validator_bond: 0,
+            min_guarantor_bond: 0,
             fair: true,
             num_validators: None,
             invulnerables: vec![],
@@ -399,6 +509,46 @@ impl ExtBuilder {
         self.slash_defer_duration = eras;
         self
     }
+    pub fn slashing_immunity_eras(mut self, eras: EraIndex) -> Self {
+        self.slashing_immunity_eras = eras;
+        self
+    }
+    pub fn authoring_points(mut self, points: (u32, u32, u32)) -> Self {
+        self.authoring_points = points;
+        self
+    }
+    pub fn max_stake_per_validator(mut self, cap: Option<Balance>) -> Self {
+        self.max_stake_per_validator = cap;
+        self
+    }
+    pub fn min_self_stake_ratio(mut self, min_self_stake_ratio: Perbill) -> Self {
+        self.min_self_stake_ratio = min_self_stake_ratio;
+        self
+    }
+    pub fn max_commission_increase_per_era(mut self, max_commission_increase_per_era: Perbill) -> Self {
+        self.max_commission_increase_per_era = max_commission_increase_per_era;
+        self
+    }
+    pub fn fallback(mut self, fallback: FallbackMode) -> Self {
+        self.fallback = fallback;
+        self
+    }
+    pub fn max_guarantees(mut self, max_guarantees: u32) -> Self {
+        self.max_guarantees = max_guarantees;
+        self
+    }
+    pub fn max_commission(mut self, max_commission: Perbill) -> Self {
+        self.max_commission = max_commission;
+        self
+    }
+    pub fn min_validator_bond(mut self, min_validator_bond: Balance) -> Self {
+        self.min_validator_bond = min_validator_bond;
+        self
+    }
+    pub fn min_guarantor_bond(mut self, min_guarantor_bond: Balance) -> Self {
+        self.min_guarantor_bond = min_guarantor_bond;
+        self
+    }
     pub fn fair(mut self, is_fair: bool) -> Self {
         self.fair = is_fair;
         self
@@ -426,6 +576,16 @@ impl ExtBuilder {
     pub fn set_associated_consts(&self) {
         EXISTENTIAL_DEPOSIT.with(|v| *v.borrow_mut() = self.existential_deposit);
         SLASH_DEFER_DURATION.with(|v| *v.borrow_mut() = self.slash_defer_duration);
+        SLASHING_IMMUNITY_ERAS.with(|v| *v.borrow_mut() = self.slashing_immunity_eras);
+        AUTHORING_POINTS.with(|v| *v.borrow_mut() = self.authoring_points);
+        MAX_STAKE_PER_VALIDATOR.with(|v| *v.borrow_mut() = self.max_stake_per_validator);
+        MIN_SELF_STAKE_RATIO.with(|v| *v.borrow_mut() = self.min_self_stake_ratio);
+        MAX_COMMISSION_INCREASE_PER_ERA.with(|v| *v.borrow_mut() = self.max_commission_increase_per_era);
+        FALLBACK.with(|v| *v.borrow_mut() = self.fallback);
+        MAX_GUARANTEES.with(|v| *v.borrow_mut() = self.max_guarantees);
+        MAX_COMMISSION.with(|v| *v.borrow_mut() = self.max_commission);
+        MIN_VALIDATOR_BOND.with(|v| *v.borrow_mut() = self.min_validator_bond);
+        MIN_GUARANTOR_BOND.with(|v| *v.borrow_mut() = self.min_guarantor_bond);
         OWN_WORKLOAD.with(|v| *v.borrow_mut() = self.own_workload);
         TOTAL_WORKLOAD.with(|v| *v.borrow_mut() = self.total_workload);
         DSM_STAKING_PAYOUT.with(|v| *v.borrow_mut() = self.dsm_staking_payout);
@@ -731,6 +891,14 @@ pub fn start_era_with_new_workloads(era_index: EraIndex, with_reward: bool, own_
     assert_eq!(Staking::current_era().unwrap_or(0), era_index);
 }
 
+pub fn staking_events() -> Vec<staking::Event<Test>> {
+    System::events()
+        .into_iter()
+        .map(|r| r.event)
+        .filter_map(|e| if let Event::staking(inner) = e { Some(inner) } else { None })
+        .collect()
+}
+
 pub fn payout_all_stakers(era_index: EraIndex) {
     Staking::reward_stakers(Origin::signed(10), 11, era_index).unwrap_or_default();
     Staking::reward_stakers(Origin::signed(10), 21, era_index).unwrap_or_default();
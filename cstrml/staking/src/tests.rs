@@ -43,6 +43,51 @@ fn force_unstake_works() {
     });
 }
 
+#[test]
+fn force_unstake_batch_works() {
+    ExtBuilder::default().build().execute_with(|| {
+        System::set_block_number(1);
+        assert_eq!(Staking::bonded(&11), Some(10));
+        assert_eq!(Staking::bonded(&21), Some(20));
+        assert_eq!(Staking::bonded(&101), Some(100));
+
+        // Force unstake batch requires root.
+        assert_noop!(Staking::force_unstake_batch(Origin::signed(11), vec![11, 21]), BadOrigin);
+        // Account 1 is not a stash, it's skipped
+        assert_ok!(Staking::force_unstake_batch(Origin::root(), vec![11, 1, 21, 101]));
+        assert_eq!(*staking_events().last().unwrap(), RawEvent::ForceUnstakeBatch(3));
+
+        for stash in &[11, 21, 101] {
+            // No longer bonded.
+            assert_eq!(Staking::bonded(stash), None);
+            // Transfer works.
+            assert_ok!(Balances::transfer(Origin::signed(*stash), 1, 10));
+        }
+        assert!(!<Validators<Test>>::contains_key(&11));
+        assert!(Staking::guarantors(&101).is_none());
+    });
+}
+
+#[test]
+fn set_stake_limits_should_work() {
+    ExtBuilder::default().build().execute_with(|| {
+        System::set_block_number(1);
+        let limits = vec![(11, 100), (21, 200), (31, 300)];
+
+        assert_noop!(Staking::set_stake_limits(Origin::signed(10), limits.clone()), BadOrigin);
+
+        assert_ok!(Staking::set_stake_limits(Origin::root(), limits));
+        assert_eq!(Staking::stake_limit(&11), Some(100));
+        assert_eq!(Staking::stake_limit(&21), Some(200));
+        assert_eq!(Staking::stake_limit(&31), Some(300));
+        assert_eq!(*staking_events().last().unwrap(), RawEvent::StakeLimitsSet(3));
+
+        // Over the batch cap
+        let limits: Vec<(u128, u128)> = (0..(MAX_STAKE_LIMITS_BATCH as u128 + 1)).map(|i| (1000 + i, 1)).collect();
+        assert_noop!(Staking::set_stake_limits(Origin::root(), limits), Error::<Test>::TooManyStakeLimits);
+    });
+}
+
 #[test]
 fn basic_setup_works() {
     // Verifies initial conditions of mock
@@ -741,6 +786,58 @@ fn no_candidate_emergency_condition() {
         });
 }
 
+#[test]
+fn insufficient_candidates_should_stall_election() {
+    ExtBuilder::default()
+        .fallback(FallbackMode::StallElection)
+        .build()
+        .execute_with(|| {
+            // Events are not recorded in the genesis block
+            System::set_block_number(1);
+            assert_eq_uvec!(validator_controllers(), vec![20, 10]);
+
+            // 11, 21 and 31 are the only candidates
+            <Staking as crate::Store>::MinimumValidatorCount::put(5);
+            start_era(1, false);
+
+            assert!(staking_events().contains(&RawEvent::InsufficientCandidates(3, 5)));
+            assert_eq_uvec!(validator_controllers(), vec![20, 10]);
+            assert_eq!(Staking::eras_stakers(1, &11), Exposure::default());
+            assert_eq!(Staking::eras_total_stakes(1), 0);
+            assert_eq!(Staking::eras_unclaimed_validators(1), 0);
+        });
+}
+
+#[test]
+fn insufficient_candidates_should_keep_current_set() {
+    ExtBuilder::default()
+        .fallback(FallbackMode::KeepCurrentSet)
+        .build()
+        .execute_with(|| {
+            // Events are not recorded in the genesis block
+            System::set_block_number(1);
+            assert_eq_uvec!(validator_controllers(), vec![20, 10]);
+            let elected = Staking::current_elected();
+
+            // 11, 21 and 31 are the only candidates
+            <Staking as crate::Store>::MinimumValidatorCount::put(5);
+            start_era(1, false);
+
+            assert!(staking_events().contains(&RawEvent::InsufficientCandidates(3, 5)));
+            assert_eq_uvec!(validator_controllers(), vec![20, 10]);
+            assert_eq!(Staking::current_elected(), elected);
+            assert_eq!(Staking::eras_stakers(1, &11), Staking::eras_stakers(0, &11));
+            assert_eq!(Staking::eras_stakers_clipped(1, &21), Staking::eras_stakers_clipped(0, &21));
+            // Only the kept set is copied, 31 isn't elected
+            assert_eq!(Staking::eras_stakers(1, &31), Exposure::default());
+            assert_eq!(
+                Staking::eras_total_stakes(1),
+                Staking::eras_stakers(0, &11).total + Staking::eras_stakers(0, &21).total
+            );
+            assert_eq!(Staking::eras_unclaimed_validators(1), 2);
+        });
+}
+
 #[test]
 fn guaranteeing_and_rewards_should_work() {
     ExtBuilder::default()
@@ -1503,6 +1600,29 @@ fn staking_and_authoring_reward_change_work() {
         })
 }
 
+#[test]
+fn inflation_info_should_work() {
+    ExtBuilder::default()
+        .guarantee(false)
+        .build()
+        .execute_with(|| {
+            start_era(1, false);
+            // Make the total issuance be 100M CRUs
+            let others = Balances::total_issuance() - Balances::free_balance(&11);
+            let _ = Balances::make_free_balance_be(&11, 100_000_000 * CRUS - others);
+            <ErasTotalStakes<Test>>::insert(1, Perbill::from_percent(35) * Balances::total_issuance());
+
+            // First year rewards are 5M CRUs, which is 5% of the total issuance
+            let (staking_ratio, inflation) = Staking::inflation_info();
+            assert_eq!(staking_ratio, Perbill::from_percent(35));
+            assert!(Perbill::from_percent(5).deconstruct() - inflation.deconstruct() <= 1);
+
+            // Nothing is minted before the reward starts
+            assert_ok!(Staking::set_start_reward_era(Origin::root(), 100));
+            assert_eq!(Staking::inflation_info(), (Perbill::from_percent(35), Perbill::zero()));
+        })
+}
+
 #[test]
 fn extra_reward_should_work() {
     ExtBuilder::default()
@@ -1777,6 +1897,40 @@ fn unbond_should_works() {
     });
 }
 
+#[test]
+fn withdraw_unbonded_events_should_work() {
+    ExtBuilder::default().build().execute_with(|| {
+        // Events are not recorded in the genesis block
+        System::set_block_number(1);
+
+        // Two chunks, unlocked at era 3 and 4
+        assert_ok!(Staking::unbond(Origin::signed(10), 200));
+        start_era(1, false);
+        assert_ok!(Staking::unbond(Origin::signed(10), 300));
+
+        // Nothing unlocked yet, no-op
+        let events_count = staking_events().len();
+        assert_ok!(Staking::withdraw_unbonded(Origin::signed(10)));
+        assert_eq!(staking_events().len(), events_count);
+
+        // The first chunk is withdrawn, the stash is still bonded
+        start_era(3, false);
+        assert_ok!(Staking::withdraw_unbonded(Origin::signed(10)));
+        assert_eq!(*staking_events().last().unwrap(), RawEvent::Withdrawn(11, 200));
+        assert!(!staking_events().contains(&RawEvent::StashReaped(11)));
+        assert_eq!(Staking::ledger(&10).unwrap().total, 800);
+
+        // Withdraw everything left, the stash is reaped
+        assert_ok!(Staking::unbond(Origin::signed(10), 500));
+        start_era(6, false);
+        assert_ok!(Staking::withdraw_unbonded(Origin::signed(10)));
+        let events = staking_events();
+        assert_eq!(events[events.len() - 2..], [RawEvent::Withdrawn(11, 800), RawEvent::StashReaped(11)]);
+        assert!(Staking::ledger(&10).is_none());
+        assert!(Staking::bonded(&11).is_none());
+    });
+}
+
 #[test]
 fn bond_extra_and_withdraw_unbonded_works() {
     // * Should test
@@ -1964,6 +2118,225 @@ fn bond_extra_and_withdraw_unbonded_works() {
         })
 }
 
+#[test]
+fn bonding_duration_override_should_work() {
+    ExtBuilder::default()
+        .guarantee(false)
+        .build()
+        .execute_with(|| {
+            // Events are not recorded in the genesis block
+            System::set_block_number(1);
+            assert_eq!(Staking::bonding_duration(), 3);
+
+            // Only root, within (SlashDeferDuration, BondingDuration]
+            assert_noop!(Staking::set_bonding_duration_override(Origin::signed(10), Some(1)), BadOrigin);
+            assert_noop!(
+                Staking::set_bonding_duration_override(Origin::root(), Some(4)),
+                Error::<Test>::InvalidBondingDuration
+            );
+            assert_noop!(
+                Staking::set_bonding_duration_override(Origin::root(), Some(0)),
+                Error::<Test>::InvalidBondingDuration
+            );
+
+            assert_ok!(Staking::set_bonding_duration_override(Origin::root(), Some(1)));
+            assert!(staking_events().contains(&RawEvent::BondingDurationSet(1)));
+            assert_eq!(Staking::bonding_duration(), 1);
+
+            // Funds unlock after 1 era instead of 3
+            start_era(1, false);
+            Staking::unbond(Origin::signed(10), 600).unwrap();
+            assert_eq!(Staking::ledger(&10).unwrap().unlocking, vec![UnlockChunk { value: 600, era: 1 + 1 }]);
+            start_era(2, false);
+            assert_ok!(Staking::withdraw_unbonded(Origin::signed(10)));
+            assert_eq!(Staking::ledger(&10).unwrap().total, 400);
+
+            // Restore the default
+            assert_ok!(Staking::set_bonding_duration_override(Origin::root(), None));
+            assert!(staking_events().contains(&RawEvent::BondingDurationSet(3)));
+            assert_eq!(Staking::bonding_duration(), 3);
+        })
+}
+
+#[test]
+fn unbond_keeps_chunks_ordered_when_duration_shortens() {
+    ExtBuilder::default()
+        .guarantee(false)
+        .build()
+        .execute_with(|| {
+            start_era(1, false);
+            Staking::unbond(Origin::signed(10), 300).unwrap();
+
+            // The new chunk unlocks earlier, it goes first
+            assert_ok!(Staking::set_bonding_duration_override(Origin::root(), Some(1)));
+            Staking::unbond(Origin::signed(10), 200).unwrap();
+            assert_eq!(
+                Staking::ledger(&10).unwrap().unlocking,
+                vec![UnlockChunk { value: 200, era: 1 + 1 }, UnlockChunk { value: 300, era: 1 + 3 }]
+            );
+
+            // The remainder is re-queued in front, the queue doesn't grow
+            start_era(2, false);
+            assert_ok!(Staking::withdraw_unbonded_partial(Origin::signed(10), 100));
+            assert_eq!(
+                Staking::ledger(&10),
+                Some(StakingLedger {
+                    stash: 11,
+                    total: 900,
+                    active: 500,
+                    unlocking: vec![UnlockChunk { value: 100, era: 2 }, UnlockChunk { value: 300, era: 1 + 3 }],
+                    claimed_rewards: vec![]
+                })
+            );
+        })
+}
+
+#[test]
+fn withdraw_unbonded_partial_works() {
+    ExtBuilder::default()
+        .guarantee(false)
+        .build()
+        .execute_with(|| {
+            start_era(1, false);
+
+            // Unbond 600 of the funds in stash.
+            Staking::unbond(Origin::signed(10), 600).unwrap();
+            assert_eq!(
+                Staking::ledger(&10),
+                Some(StakingLedger {
+                    stash: 11,
+                    total: 1000,
+                    active: 400,
+                    unlocking: vec![UnlockChunk { value: 600, era: 1 + 3 }],
+                    claimed_rewards: vec![]
+                })
+            );
+
+            // Nothing is unlocked yet.
+            assert_ok!(Staking::withdraw_unbonded_partial(Origin::signed(10), 300));
+            assert_eq!(Staking::ledger(&10).unwrap().total, 1000);
+
+            // A later chunk which is still locked
+            start_era(2, false);
+            Staking::unbond(Origin::signed(10), 100).unwrap();
+
+            start_era(4, false);
+
+            // Withdraw half of the unlocked funds, the rest stays queued in era order.
+            assert_ok!(Staking::withdraw_unbonded_partial(Origin::signed(10), 300));
+            assert_eq!(
+                Staking::ledger(&10),
+                Some(StakingLedger {
+                    stash: 11,
+                    total: 700,
+                    active: 300,
+                    unlocking: vec![UnlockChunk { value: 300, era: 4 }, UnlockChunk { value: 100, era: 2 + 3 }],
+                    claimed_rewards: vec![]
+                })
+            );
+            assert_eq!(Balances::locks(&11)[0].amount, 700);
+
+            // Asking for more than what's unlocked withdraws everything available.
+            assert_ok!(Staking::withdraw_unbonded_partial(Origin::signed(10), 1000));
+            assert_eq!(
+                Staking::ledger(&10),
+                Some(StakingLedger {
+                    stash: 11,
+                    total: 400,
+                    active: 300,
+                    unlocking: vec![UnlockChunk { value: 100, era: 2 + 3 }],
+                    claimed_rewards: vec![]
+                })
+            );
+            assert_eq!(Balances::locks(&11)[0].amount, 400);
+        })
+}
+
+#[test]
+fn ledger_lock_tracks_total_changes() {
+    ExtBuilder::default()
+        .guarantee(false)
+        .build()
+        .execute_with(|| {
+            start_era(1, false);
+            assert_eq!(Balances::locks(&11)[0].amount, 1000);
+
+            // Total increases, so does the lock
+            let _ = Balances::make_free_balance_be(&11, 2000);
+            assert_ok!(Staking::bond_extra(Origin::signed(11), 500));
+            assert_eq!(Staking::ledger(&10).unwrap().total, 1500);
+            assert_eq!(Balances::locks(&11)[0].amount, 1500);
+
+            // Unbonding doesn't change the total, the lock stays
+            assert_ok!(Staking::unbond(Origin::signed(10), 600));
+            assert_eq!(Staking::ledger(&10).unwrap().total, 1500);
+            assert_eq!(Balances::locks(&11)[0].amount, 1500);
+
+            // Nothing is unlocked yet, the lock is still set back
+            assert_ok!(Staking::withdraw_unbonded(Origin::signed(10)));
+            assert_eq!(Staking::ledger(&10).unwrap().total, 1500);
+            assert_eq!(Balances::locks(&11)[0].amount, 1500);
+
+            // The lock isn't written at all when the total doesn't change
+            Balances::set_lock(STAKING_ID, &11, 1, WithdrawReasons::all());
+            assert_ok!(Staking::unbond(Origin::signed(10), 100));
+            assert_eq!(Staking::ledger(&10).unwrap().total, 1500);
+            assert_eq!(Balances::locks(&11)[0].amount, 1);
+            Balances::set_lock(STAKING_ID, &11, 1500, WithdrawReasons::all());
+
+            // Total decreases, so does the lock
+            start_era(4, false);
+            assert_ok!(Staking::withdraw_unbonded(Origin::signed(10)));
+            assert_eq!(Staking::ledger(&10).unwrap().total, 800);
+            assert_eq!(Balances::locks(&11)[0].amount, 800);
+        })
+}
+
+#[test]
+fn consolidate_chunks_works() {
+    ExtBuilder::default()
+        .guarantee(false)
+        .build()
+        .execute_with(|| {
+            start_era(1, false);
+
+            // Three chunks unlocked at era 4 and one at era 5.
+            Staking::unbond(Origin::signed(10), 100).unwrap();
+            Staking::unbond(Origin::signed(10), 200).unwrap();
+            Staking::unbond(Origin::signed(10), 300).unwrap();
+            start_era(2, false);
+            Staking::unbond(Origin::signed(10), 50).unwrap();
+            assert_eq!(Staking::ledger(&10).unwrap().unlocking.len(), 4);
+
+            assert_ok!(Staking::consolidate_chunks(Origin::signed(10)));
+            assert!(staking_events().contains(&RawEvent::ChunksConsolidated(11, 2)));
+            assert_eq!(
+                Staking::ledger(&10),
+                Some(StakingLedger {
+                    stash: 11,
+                    total: 1000,
+                    active: 350,
+                    unlocking: vec![
+                        UnlockChunk { value: 600, era: 1 + 3 },
+                        UnlockChunk { value: 50, era: 2 + 3 },
+                    ],
+                    claimed_rewards: vec![]
+                })
+            );
+
+            // Nothing left to merge.
+            assert_ok!(Staking::consolidate_chunks(Origin::signed(10)));
+            assert!(staking_events().contains(&RawEvent::ChunksConsolidated(11, 0)));
+            assert_eq!(Staking::ledger(&10).unwrap().unlocking.len(), 2);
+
+            // Only a controller can consolidate.
+            assert_noop!(
+                Staking::consolidate_chunks(Origin::signed(11)),
+                Error::<Test>::NotController
+            );
+        })
+}
+
 #[test]
 fn rebond_works() {
     // * Should test
@@ -2423,6 +2796,38 @@ fn on_free_balance_zero_stash_removes_validator() {
         });
 }
 
+#[test]
+fn reap_stash_with_dust_bond_should_work() {
+    ExtBuilder::default()
+        .existential_deposit(10)
+        .build()
+        .execute_with(|| {
+            // The stash still has funds and they are bonded
+            assert_eq!(Balances::free_balance(&11), 256000);
+            assert_noop!(Staking::reap_stash(Origin::none(), 11), Error::<Test>::FundsStillBonded);
+
+            // Slashed right at the existential deposit
+            let mut ledger = Staking::ledger(&10).unwrap();
+            ledger.total = 10;
+            ledger.active = 10;
+            <Ledger<Test>>::insert(&10, ledger.clone());
+            assert_noop!(Staking::reap_stash(Origin::none(), 11), Error::<Test>::FundsStillBonded);
+
+            // Slashed below the existential deposit
+            ledger.total = 9;
+            ledger.active = 9;
+            <Ledger<Test>>::insert(&10, ledger);
+            assert_ok!(Staking::reap_stash(Origin::none(), 11));
+            assert!(!<Ledger<Test>>::contains_key(&10));
+            assert!(!<Bonded<Test>>::contains_key(&11));
+            assert!(!<Validators<Test>>::contains_key(&11));
+            assert!(Balances::locks(&11).is_empty());
+
+            // Not a stash anymore
+            assert_noop!(Staking::reap_stash(Origin::none(), 11), Error::<Test>::NotStash);
+        });
+}
+
 #[test]
 fn on_free_balance_zero_stash_removes_guarantor() {
     // Tests that guarantor storage items are cleaned up when stash is empty
@@ -3047,58 +3452,237 @@ fn reward_from_authorship_event_handler_works() {
 }
 
 #[test]
-fn add_reward_points_fns_works() {
-    ExtBuilder::default().build().execute_with(|| {
-        // Not mandatory but must be coherent with rewards
-        assert_eq!(Session::validators(), vec![11, 21]);
-
-        <Module<Test>>::reward_by_ids(vec![
-            (11, 1),
-            (21, 1),
-            (21, 1),
-        ]);
+fn reward_from_authorship_event_handler_with_custom_points_works() {
+    ExtBuilder::default()
+        .authoring_points((10, 3, 2))
+        .build()
+        .execute_with(|| {
+            use pallet_authorship::EventHandler;
 
-        <Module<Test>>::reward_by_ids(vec![
-            (11, 1),
-            (21, 1),
-            (21, 1),
-        ]);
+            <Module<Test>>::note_author(11);
+            <Module<Test>>::note_uncle(21, 1);
 
-        assert_eq!(
-            ErasRewardPoints::<Test>::get(Staking::active_era().unwrap().index),
-            EraRewardPoints {
-                individual: vec![(21, 4), (11, 2)].into_iter().collect(),
-                total: 6,
-            },
-        );
-    })
+            assert_eq!(
+                ErasRewardPoints::<Test>::get(Staking::active_era().unwrap().index),
+                EraRewardPoints {
+                    individual: vec![(11, 10 + 3), (21, 2)].into_iter().collect(),
+                    total: 15,
+                },
+            );
+        })
 }
 
 #[test]
-fn unbonded_balance_is_not_slashable() {
+fn blocks_authored_should_work() {
     ExtBuilder::default().build().execute_with(|| {
-        // total amount staked is slashable.
-        assert_eq!(Staking::slashable_balance_of(&11), 1000);
+        use pallet_authorship::EventHandler;
 
-        assert_ok!(Staking::unbond(Origin::signed(10), 800));
+        <Module<Test>>::note_author(11);
+        <Module<Test>>::note_author(11);
+        <Module<Test>>::note_author(21);
+        // Uncles don't count
+        <Module<Test>>::note_uncle(21, 1);
+        assert_eq!(Staking::eras_blocks_authored(0, 11), 2);
+        assert_eq!(Staking::eras_blocks_authored(0, 21), 1);
+        assert_eq!(Staking::eras_blocks_authored(0, 31), 0);
 
-        // only the active portion.
-        assert_eq!(Staking::slashable_balance_of(&11), 200);
+        start_era(1, false);
+        <Module<Test>>::note_author(21);
+        assert_eq!(Staking::eras_blocks_authored(1, 21), 1);
+        assert_eq!(Staking::eras_blocks_authored(0, 21), 1);
+
+        // Pruned with the other era information
+        HistoryDepth::put(1);
+        start_era(3, false);
+        assert_eq!(Staking::eras_blocks_authored(0, 11), 0);
+        assert_eq!(Staking::eras_blocks_authored(1, 21), 0);
     })
 }
 
 #[test]
-fn era_is_always_same_length() {
-    // This ensures that the sessions is always of the same length if there is no forcing no
-    // session changes.
+fn validator_headroom_should_work() {
     ExtBuilder::default().build().execute_with(|| {
-        let session_per_era = <SessionsPerEra as Get<SessionIndex>>::get();
+        let exposed = Staking::eras_stakers(0, 11).total;
+        assert_eq!(exposed, 1250);
 
-        start_era(1, false);
-        assert_eq!(Staking::eras_start_session_index(Staking::current_era().unwrap()).unwrap(), session_per_era);
+        Staking::upsert_stake_limit(&11, 2000);
+        assert_eq!(Staking::validator_headroom(&11), 2000 - exposed);
 
-        start_era(2, false);
-        assert_eq!(Staking::eras_start_session_index(Staking::current_era().unwrap()).unwrap(), session_per_era * 2u32);
+        // Already over the limit
+        Staking::upsert_stake_limit(&11, 1000);
+        assert_eq!(Staking::validator_headroom(&11), 0);
+
+        // Not a validator
+        assert_eq!(Staking::validator_headroom(&1), 0);
+    })
+}
+
+#[test]
+fn active_backing_should_work() {
+    ExtBuilder::default()
+        .validator_pool(true)
+        .guarantee(false)
+        .build()
+        .execute_with(|| {
+            // 101 also guarantees 41, which won't be elected
+            assert_ok!(Staking::guarantee(Origin::signed(100), (11, 200)));
+            assert_ok!(Staking::guarantee(Origin::signed(100), (21, 200)));
+            assert_ok!(Staking::guarantee(Origin::signed(100), (41, 100)));
+            assert_eq!(Staking::guarantors(&101).unwrap().targets.len(), 3);
+
+            start_era(1, false);
+            assert_eq_uvec!(validator_controllers(), vec![20, 10]);
+
+            let backed_value = |v_stash| Staking::eras_stakers(1, v_stash).others.iter()
+                .find(|o| o.who == 101).unwrap().value;
+            assert_eq_uvec!(
+                Staking::active_backing(&101),
+                vec![(11, backed_value(11)), (21, backed_value(21))]
+            );
+            assert_eq!(Staking::active_backing(&41), vec![]);
+        })
+}
+
+#[test]
+fn current_validator_set_should_work() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_ok!(Staking::validate(Origin::signed(10), ValidatorPrefs { fee: Perbill::from_percent(50) }));
+        start_era(1, false);
+
+        let validators = Staking::current_validator_set();
+        assert_eq_uvec!(validators.iter().map(|v| v.stash).collect::<Vec<_>>(), vec![11, 21]);
+        for v in &validators {
+            let exposure = Staking::eras_stakers(1, &v.stash);
+            assert_eq!(v.own, exposure.own);
+            assert_eq!(v.total, exposure.total);
+            assert_eq!(v.guarantors_count, exposure.others.len() as u32);
+            assert_eq!(v.fee, Staking::eras_validator_prefs(1, &v.stash).fee);
+        }
+        let v_11 = validators.iter().find(|v| v.stash == 11).unwrap();
+        assert_eq!(v_11.guarantors_count, 1);
+        assert_eq!(v_11.fee, Perbill::from_percent(50));
+
+        // An elected validator without exposure has zero stakes
+        <CurrentElected<Test>>::put(vec![11, 21, 41]);
+        let v_41 = Staking::current_validator_set().into_iter().find(|v| v.stash == 41).unwrap();
+        assert_eq!(v_41, ValidatorInfo { stash: 41, own: 0, total: 0, guarantors_count: 0, fee: ValidatorPrefs::default().fee });
+    })
+}
+
+#[test]
+fn era_transition_events_should_work() {
+    ExtBuilder::default().build().execute_with(|| {
+        System::set_block_number(1);
+        start_era(1, true);
+
+        let events = staking_events();
+        assert!(events.contains(&RawEvent::NewEra(1, 2)));
+        let staking_payout = Staking::eras_staking_payout(0).unwrap();
+        assert!(events.iter().any(|e| matches!(e,
+            RawEvent::EraReward(0, _, payout) if *payout == staking_payout
+        )));
+    })
+}
+
+#[test]
+fn era_points_should_work() {
+    ExtBuilder::default().build().execute_with(|| {
+        System::set_block_number(1);
+        start_era(1, true);
+        assert_eq!(Staking::current_era_points(), (0, vec![]));
+
+        <Module<Test>>::reward_by_ids(vec![(11, 20), (21, 10)]);
+        <Module<Test>>::reward_by_ids(vec![(11, 5)]);
+        assert_eq!(Staking::current_era_points(), (35, vec![(11, 25), (21, 10)]));
+
+        start_era(2, true);
+        assert!(staking_events().contains(&RawEvent::EraPoints(1, 35, vec![(11, 25), (21, 10)])));
+        assert_eq!(Staking::current_era_points(), (0, vec![]));
+    })
+}
+
+#[test]
+fn set_history_depth_should_work() {
+    ExtBuilder::default().build().execute_with(|| {
+        start_era(10, false);
+        assert_noop!(Staking::set_history_depth(Origin::signed(1), 5, 0), BadOrigin);
+
+        // Shrinking the depth prunes immediately
+        assert!(<ErasStakers<Test>>::contains_key(4, &11));
+        assert!(ErasStartSessionIndex::contains_key(4));
+        assert_ok!(Staking::set_history_depth(Origin::root(), 5, 5));
+        assert_eq!(Staking::history_depth(), 5);
+        assert!(!<ErasStakers<Test>>::contains_key(0, &11));
+        assert!(!<ErasStakers<Test>>::contains_key(4, &11));
+        assert!(!ErasStartSessionIndex::contains_key(4));
+        assert!(<ErasStakers<Test>>::contains_key(5, &11));
+        assert!(ErasStartSessionIndex::contains_key(5));
+
+        // Old eras are pruned as they fall outside the depth
+        start_era(11, false);
+        assert!(!<ErasStakers<Test>>::contains_key(5, &11));
+        assert!(<ErasStakers<Test>>::contains_key(6, &11));
+
+        // Growing the depth keeps everything
+        assert_ok!(Staking::set_history_depth(Origin::root(), 84, 0));
+        start_era(12, false);
+        assert!(<ErasStakers<Test>>::contains_key(6, &11));
+    })
+}
+
+#[test]
+fn add_reward_points_fns_works() {
+    ExtBuilder::default().build().execute_with(|| {
+        // Not mandatory but must be coherent with rewards
+        assert_eq!(Session::validators(), vec![11, 21]);
+
+        <Module<Test>>::reward_by_ids(vec![
+            (11, 1),
+            (21, 1),
+            (21, 1),
+        ]);
+
+        <Module<Test>>::reward_by_ids(vec![
+            (11, 1),
+            (21, 1),
+            (21, 1),
+        ]);
+
+        assert_eq!(
+            ErasRewardPoints::<Test>::get(Staking::active_era().unwrap().index),
+            EraRewardPoints {
+                individual: vec![(21, 4), (11, 2)].into_iter().collect(),
+                total: 6,
+            },
+        );
+    })
+}
+
+#[test]
+fn unbonded_balance_is_not_slashable() {
+    ExtBuilder::default().build().execute_with(|| {
+        // total amount staked is slashable.
+        assert_eq!(Staking::slashable_balance_of(&11), 1000);
+
+        assert_ok!(Staking::unbond(Origin::signed(10), 800));
+
+        // only the active portion.
+        assert_eq!(Staking::slashable_balance_of(&11), 200);
+    })
+}
+
+#[test]
+fn era_is_always_same_length() {
+    // This ensures that the sessions is always of the same length if there is no forcing no
+    // session changes.
+    ExtBuilder::default().build().execute_with(|| {
+        let session_per_era = <SessionsPerEra as Get<SessionIndex>>::get();
+
+        start_era(1, false);
+        assert_eq!(Staking::eras_start_session_index(Staking::current_era().unwrap()).unwrap(), session_per_era);
+
+        start_era(2, false);
+        assert_eq!(Staking::eras_start_session_index(Staking::current_era().unwrap()).unwrap(), session_per_era * 2u32);
 
         let session = Session::current_index();
         ForceEra::put(Forcing::ForceNew);
@@ -3160,6 +3744,201 @@ fn offence_deselects_validator_when_slash_is_zero() {
     });
 }
 
+#[test]
+fn max_stake_per_validator_should_work() {
+    ExtBuilder::default()
+        .max_stake_per_validator(Some(1100))
+        .build()
+        .execute_with(|| {
+            // 1000 + 250 is capped to 1100
+            assert_eq!(
+                Staking::eras_stakers(0, &11),
+                Exposure {
+                    total: 1100,
+                    own: 1000,
+                    others: vec![IndividualExposure { who: 101, value: 100 }]
+                }
+            );
+
+            // The smallest guarantor is trimmed first
+            System::set_block_number(1);
+            bond_guarantor(200, 500, vec![(11, 300)]);
+            start_era(1, false);
+            assert_eq!(
+                Staking::eras_stakers(1, &11),
+                Exposure {
+                    total: 1100,
+                    own: 1000,
+                    others: vec![IndividualExposure { who: 201, value: 100 }]
+                }
+            );
+            // Both the dropped and the trimmed guarantors are notified
+            assert!(staking_events().contains(&RawEvent::GuarantorStakeReduced(101, 11, 250, 0)));
+            assert!(staking_events().contains(&RawEvent::GuarantorStakeReduced(201, 11, 300, 100)));
+            assert_eq!(
+                Staking::eras_stakers_clipped(1, &11),
+                Staking::eras_stakers(1, &11)
+            );
+        })
+}
+
+#[test]
+fn stake_limit_reduced_events_should_work() {
+    ExtBuilder::default().build().execute_with(|| {
+        System::set_block_number(1);
+        // (a) 31 is under its limit, (b) 11 is cut to half, (c) 21 has no limit anymore
+        Staking::upsert_stake_limit(&11, 625);
+        Staking::upsert_stake_limit(&21, 0);
+        start_era(1, false);
+
+        assert_eq!(
+            Staking::eras_stakers(1, &11),
+            Exposure {
+                total: 625,
+                own: 500,
+                others: vec![IndividualExposure { who: 101, value: 125 }]
+            }
+        );
+        let events = staking_events();
+        assert!(events.contains(&RawEvent::StakeLimitReduced(11, 1250, 625)));
+        assert!(events.contains(&RawEvent::GuarantorStakeReduced(101, 11, 250, 125)));
+        assert!(events.contains(&RawEvent::StakeLimitReduced(21, 1250, 0)));
+        assert!(events.contains(&RawEvent::GuarantorStakeReduced(101, 21, 250, 0)));
+        assert!(!events.iter().any(|e| matches!(e, RawEvent::StakeLimitReduced(31, ..))));
+    })
+}
+
+#[test]
+fn min_self_stake_ratio_should_work() {
+    ExtBuilder::default()
+        .min_self_stake_ratio(Perbill::from_percent(80))
+        .build()
+        .execute_with(|| {
+            // Events are not recorded in the genesis block
+            System::set_block_number(1);
+            // 1000 of 1250 is exactly 80%, both are elected
+            assert_eq_uvec!(validator_controllers(), vec![20, 10]);
+
+            // 1000 of 1750 is below 80%, 21 is excluded
+            bond_guarantor(200, 1000, vec![(21, 500)]);
+            start_era(1, false);
+            assert!(staking_events().contains(&RawEvent::ValidatorExcludedLowSelfStake(21)));
+            assert!(!staking_events().contains(&RawEvent::ValidatorExcludedLowSelfStake(11)));
+            assert_eq!(Staking::eras_stakers(1, &21), Exposure::default());
+            assert_eq!(Staking::eras_stakers(1, &11).total, 1250);
+            assert!(validator_controllers().contains(&10));
+            assert!(!validator_controllers().contains(&20));
+        })
+}
+
+#[test]
+fn validator_without_ledger_should_be_excluded() {
+    ExtBuilder::default().build().execute_with(|| {
+        // Events are not recorded in the genesis block
+        System::set_block_number(1);
+        assert_eq_uvec!(validator_controllers(), vec![20, 10]);
+
+        // The ledger of 11 is gone before the election
+        <Ledger<Test>>::remove(&10);
+        start_era(1, false);
+        assert_eq!(Staking::current_era(), Some(1));
+        assert!(staking_events().contains(&RawEvent::ValidatorExcludedNoLedger(11)));
+        assert!(!staking_events().contains(&RawEvent::ValidatorExcludedNoLedger(21)));
+        assert_eq!(Staking::eras_stakers(1, &11), Exposure::default());
+        assert!(!Staking::current_elected().contains(&11));
+        assert!(!validator_controllers().contains(&10));
+        assert!(validator_controllers().contains(&20));
+    });
+}
+
+#[test]
+fn max_commission_should_work() {
+    ExtBuilder::default()
+        .max_commission(Perbill::from_percent(20))
+        .build()
+        .execute_with(|| {
+            // Above the cap: the validator takes 30%
+            assert_noop!(
+                Staking::validate(Origin::signed(10), ValidatorPrefs { fee: Perbill::from_percent(70) }),
+                Error::<Test>::CommissionTooHigh
+            );
+            // At the cap
+            assert_ok!(Staking::validate(Origin::signed(10), ValidatorPrefs { fee: Perbill::from_percent(80) }));
+            assert_eq!(Staking::validators(&11).fee, Perbill::from_percent(80));
+            // Below the cap
+            assert_ok!(Staking::validate(Origin::signed(20), ValidatorPrefs { fee: Perbill::from_percent(90) }));
+            assert_eq!(Staking::validators(&21).fee, Perbill::from_percent(90));
+        });
+}
+
+#[test]
+fn max_commission_increase_per_era_should_work() {
+    ExtBuilder::default()
+        .max_commission_increase_per_era(Perbill::from_percent(10))
+        .build()
+        .execute_with(|| {
+            assert_eq!(Staking::validators(&11).fee, Perbill::one());
+
+            // Too large increase of the commission, i.e. decrease of the fee
+            assert_noop!(
+                Staking::validate(Origin::signed(10), ValidatorPrefs { fee: Perbill::from_percent(80) }),
+                Error::<Test>::CommissionIncreaseTooFast
+            );
+
+            // Gradual increases within the era are accumulated
+            assert_ok!(Staking::validate(Origin::signed(10), ValidatorPrefs { fee: Perbill::from_percent(95) }));
+            assert_ok!(Staking::validate(Origin::signed(10), ValidatorPrefs { fee: Perbill::from_percent(90) }));
+            assert_noop!(
+                Staking::validate(Origin::signed(10), ValidatorPrefs { fee: Perbill::from_percent(89) }),
+                Error::<Test>::CommissionIncreaseTooFast
+            );
+            assert_eq!(Staking::commission_changed_at(&11), Some((0, Perbill::zero())));
+
+            // The limit is renewed in the next era
+            start_era(1, false);
+            assert_ok!(Staking::validate(Origin::signed(10), ValidatorPrefs { fee: Perbill::from_percent(80) }));
+            assert_eq!(Staking::commission_changed_at(&11), Some((1, Perbill::from_percent(10))));
+
+            // Decrease is always allowed
+            assert_ok!(Staking::validate(Origin::signed(10), ValidatorPrefs { fee: Perbill::one() }));
+
+            // Chilling doesn't reset the limit
+            start_era(2, false);
+            assert_ok!(Staking::chill(Origin::signed(10)));
+            assert_eq!(Staking::commission_changed_at(&11), Some((2, Perbill::zero())));
+            assert_noop!(
+                Staking::validate(Origin::signed(10), ValidatorPrefs { fee: Perbill::zero() }),
+                Error::<Test>::CommissionIncreaseTooFast
+            );
+            assert_ok!(Staking::validate(Origin::signed(10), ValidatorPrefs { fee: Perbill::from_percent(90) }));
+
+            // A new validator has no previous commission to limit
+            let _ = Balances::make_free_balance_be(&51, 1000);
+            assert_ok!(Staking::bond(Origin::signed(51), 50, 1000));
+            assert_ok!(Staking::validate(Origin::signed(50), ValidatorPrefs { fee: Perbill::zero() }));
+        })
+}
+
+#[test]
+fn guarantee_target_at_limit_should_warn() {
+    ExtBuilder::default().build().execute_with(|| {
+        // Events are not recorded in the genesis block
+        System::set_block_number(1);
+        assert_eq!(Staking::eras_stakers(0, &11).total, 1250);
+        assert_eq!(Staking::stake_limit(&11), Some(2000));
+
+        // 11 isn't at its limit yet, even if the new votes would exceed it
+        bond_guarantor(200, 1000, vec![(11, 800)]);
+        assert!(!staking_events().contains(&RawEvent::TargetAtLimit(201, 11)));
+
+        // 11 is saturated, the guarantee still works
+        Staking::upsert_stake_limit(&11, 1250);
+        assert_ok!(Staking::guarantee(Origin::signed(200), (11, 100)));
+        assert!(staking_events().contains(&RawEvent::TargetAtLimit(201, 11)));
+        assert_eq!(Staking::guarantors(&201).unwrap().total, 900);
+    })
+}
+
 #[test]
 fn slashing_performed_according_exposure() {
     // This test checks that slashing is performed according the exposure (or more precisely,
@@ -3188,6 +3967,63 @@ fn slashing_performed_according_exposure() {
     });
 }
 
+#[test]
+fn slashing_immunity_should_work() {
+    ExtBuilder::default()
+        .slashing_immunity_eras(2)
+        .build()
+        .execute_with(|| {
+            start_era(2, false);
+
+            // 51 bonded at era 2, it is immune in era 2 and 3
+            bond_validator(50, 1000);
+            assert_eq!(Staking::bonded_at(&51), Some(2));
+
+            on_offence_now(
+                &[OffenceDetails {
+                    offender: (51, Exposure { total: 1000, own: 1000, others: vec![] }),
+                    reporters: vec![],
+                }],
+                &[Perbill::from_percent(10)],
+            );
+            assert_eq!(Balances::free_balance(&51), 1000);
+            assert!(<Validators<Test>>::contains_key(&51));
+
+            // Immunity window is over
+            start_era(4, false);
+            on_offence_now(
+                &[OffenceDetails {
+                    offender: (51, Exposure { total: 1000, own: 1000, others: vec![] }),
+                    reporters: vec![],
+                }],
+                &[Perbill::from_percent(10)],
+            );
+            assert_eq!(Balances::free_balance(&51), 900);
+            assert!(!<Validators<Test>>::contains_key(&51));
+        })
+}
+
+#[test]
+fn genesis_stakers_have_no_slashing_immunity() {
+    ExtBuilder::default()
+        .slashing_immunity_eras(2)
+        .build()
+        .execute_with(|| {
+            assert_eq!(Staking::bonded_at(&11), None);
+
+            // 11 is active in era 0, its offence is slashed
+            on_offence_now(
+                &[OffenceDetails {
+                    offender: (11, Staking::eras_stakers(0, &11)),
+                    reporters: vec![],
+                }],
+                &[Perbill::from_percent(10)],
+            );
+            assert_eq!(Balances::free_balance(&11), 900);
+            assert!(!<Validators<Test>>::contains_key(&11));
+        })
+}
+
 #[test]
 fn slash_in_old_span_does_not_deselect() {
     ExtBuilder::default()
@@ -3438,6 +4274,34 @@ fn only_slash_for_max_in_era() {
     })
 }
 
+#[test]
+fn repeated_slash_in_era_applies_once() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_eq!(Balances::free_balance(&11), 1000);
+        let guarantor_balance = Balances::free_balance(&101);
+        let exposure = Staking::eras_stakers(0, &11);
+        let guaranteed_value = exposure.others.iter().find(|o| o.who == 101).unwrap().value;
+
+        for _ in 0..2 {
+            on_offence_now(
+                &[OffenceDetails {
+                    offender: (11, exposure.clone()),
+                    reporters: vec![],
+                }],
+                &[Perbill::from_percent(10)],
+            );
+        }
+
+        // The same fraction reported twice is only slashed once
+        assert_eq!(Balances::free_balance(&11), 900);
+        assert_eq!(
+            Balances::free_balance(&101),
+            guarantor_balance - Perbill::from_percent(10) * guaranteed_value
+        );
+        assert_ledger_consistent(11);
+    })
+}
+
 #[test]
 fn garbage_collection_after_slashing() {
     ExtBuilder::default()
@@ -3665,86 +4529,317 @@ fn slashes_are_summed_across_spans() {
         assert_eq!(get_span(21).iter().collect::<Vec<_>>(), expected_spans);
         assert_eq!(Balances::free_balance(&21), 1900);
 
-        // 21 has been force-chilled. re-signal intent to validate.
-        Staking::validate(Origin::signed(20), Default::default()).unwrap();
+        // 21 has been force-chilled. re-signal intent to validate.
+        Staking::validate(Origin::signed(20), Default::default()).unwrap();
+
+        start_era(4, false);
+
+        assert_eq!(Staking::slashable_balance_of(&21), 900);
+
+        on_offence_now(
+            &[OffenceDetails {
+                offender: (21, Staking::eras_stakers(4, &21)),
+                reporters: vec![],
+            }],
+            &[Perbill::from_percent(10)],
+        );
+
+        let expected_spans = vec![
+            slashing::SlashingSpan {
+                index: 2,
+                start: 5,
+                length: None,
+            },
+            slashing::SlashingSpan {
+                index: 1,
+                start: 4,
+                length: Some(1),
+            },
+            slashing::SlashingSpan {
+                index: 0,
+                start: 0,
+                length: Some(4),
+            },
+        ];
+
+        assert_eq!(get_span(21).iter().collect::<Vec<_>>(), expected_spans);
+        assert_eq!(Balances::free_balance(&21), 1810);
+    });
+}
+
+#[test]
+fn deferred_slashes_are_deferred() {
+    ExtBuilder::default()
+        .slash_defer_duration(2)
+        .build()
+        .execute_with(|| {
+            start_era(1, false);
+
+            assert_eq!(Balances::free_balance(&11), 1000);
+
+            let exposure = Staking::eras_stakers(0, &11);
+            assert_eq!(Balances::free_balance(&101), 2000);
+            let guaranteed_value = exposure.others.iter().find(|o| o.who == 101).unwrap().value;
+
+            on_offence_now(
+                &[OffenceDetails {
+                    offender: (11, Staking::eras_stakers(0, &11)),
+                    reporters: vec![],
+                }],
+                &[Perbill::from_percent(10)],
+            );
+
+            assert_eq!(Balances::free_balance(&11), 1000);
+            assert_eq!(Balances::free_balance(&101), 2000);
+
+            start_era(2, false);
+
+            assert_eq!(Balances::free_balance(&11), 1000);
+            assert_eq!(Balances::free_balance(&101), 2000);
+
+            start_era(3, false);
+
+            assert_eq!(Balances::free_balance(&11), 1000);
+            assert_eq!(Balances::free_balance(&101), 2000);
+
+            // at the start of era 4, slashes from era 1 are processed,
+            // after being deferred for at least 2 full eras.
+            start_era(4, false);
+
+            assert_eq!(Balances::free_balance(&11), 900);
+            assert_eq!(Balances::free_balance(&101), 2000 - (guaranteed_value / 10));
+        })
+}
+
+#[test]
+fn deferred_slash_applied_event_should_work() {
+    ExtBuilder::default()
+        .slash_defer_duration(2)
+        .build()
+        .execute_with(|| {
+            start_era(1, false);
+
+            let exposure = Staking::eras_stakers(0, &11);
+            let guaranteed_value = exposure.others.iter().find(|o| o.who == 101).unwrap().value;
+
+            on_offence_now(
+                &[OffenceDetails {
+                    offender: (11, exposure),
+                    reporters: vec![],
+                }],
+                &[Perbill::from_percent(10)],
+            );
+
+            let applied = RawEvent::DeferredSlashApplied(1, 11, 100 + guaranteed_value / 10);
+            start_era(3, false);
+            assert!(!staking_events().contains(&applied));
+
+            // Applied at the start of era 4
+            start_era(4, false);
+            assert_eq!(Balances::free_balance(&11), 900);
+            assert!(staking_events().contains(&applied));
+        })
+}
+
+#[test]
+fn validator_risk_should_work() {
+    ExtBuilder::default()
+        .slash_defer_duration(2)
+        .build()
+        .execute_with(|| {
+            start_era(1, false);
+            let no_risk = ValidatorRisk { recent_offences: 0, slashing_spans: 0, pending_slash: false };
+            assert_eq!(Staking::validator_risk(&11), no_risk);
+
+            on_offence_now(
+                &[OffenceDetails {
+                    offender: (11, Staking::eras_stakers(1, &11)),
+                    reporters: vec![],
+                }],
+                &[Perbill::from_percent(10)],
+            );
+
+            let spans = <Staking as crate::Store>::SlashingSpans::get(&11).unwrap().iter().count() as u32;
+            assert!(spans > 0);
+            assert_eq!(
+                Staking::validator_risk(&11),
+                ValidatorRisk { recent_offences: 1, slashing_spans: spans, pending_slash: true }
+            );
+            assert_eq!(Staking::validator_risk(&21), no_risk);
 
-        start_era(4, false);
+            // The slash is applied at the start of era 4, the offence is still recorded
+            start_era(4, false);
+            assert_eq!(
+                Staking::validator_risk(&11),
+                ValidatorRisk { recent_offences: 1, slashing_spans: spans, pending_slash: false }
+            );
+        })
+}
 
-        assert_eq!(Staking::slashable_balance_of(&21), 900);
+#[test]
+fn at_risk_stake_should_work() {
+    ExtBuilder::default()
+        .slash_defer_duration(2)
+        .build()
+        .execute_with(|| {
+            start_era(1, false);
+            assert_eq!(Staking::at_risk_stake(&101), 0);
 
-        on_offence_now(
-            &[OffenceDetails {
-                offender: (21, Staking::eras_stakers(4, &21)),
-                reporters: vec![],
-            }],
-            &[Perbill::from_percent(10)],
-        );
+            on_offence_now(
+                &[OffenceDetails {
+                    offender: (11, Staking::eras_stakers(1, &11)),
+                    reporters: vec![],
+                }],
+                &[Perbill::from_percent(10)],
+            );
 
-        let expected_spans = vec![
-            slashing::SlashingSpan {
-                index: 2,
-                start: 5,
-                length: None,
-            },
-            slashing::SlashingSpan {
-                index: 1,
-                start: 4,
-                length: Some(1),
-            },
-            slashing::SlashingSpan {
-                index: 0,
-                start: 0,
-                length: Some(4),
-            },
-        ];
+            // 101 backs 11 and 21, only 11 has a pending slash
+            let guaranteed_value = Staking::eras_stakers(1, &11).others.iter()
+                .find(|o| o.who == 101).unwrap().value;
+            assert_eq!(Staking::at_risk_stake(&101), guaranteed_value);
+            assert_eq!(Staking::at_risk_stake(&41), 0);
 
-        assert_eq!(get_span(21).iter().collect::<Vec<_>>(), expected_spans);
-        assert_eq!(Balances::free_balance(&21), 1810);
-    });
+            // The slash is applied at the start of era 4
+            start_era(4, false);
+            assert_eq!(Staking::at_risk_stake(&101), 0);
+        })
 }
 
 #[test]
-fn deferred_slashes_are_deferred() {
+fn pending_slashes_of_should_work() {
     ExtBuilder::default()
         .slash_defer_duration(2)
         .build()
         .execute_with(|| {
             start_era(1, false);
+            assert!(Staking::pending_slashes_of(&11).is_empty());
+            assert!(Staking::pending_slashes_of(&101).is_empty());
 
-            assert_eq!(Balances::free_balance(&11), 1000);
-
-            let exposure = Staking::eras_stakers(0, &11);
-            assert_eq!(Balances::free_balance(&101), 2000);
-            let guaranteed_value = exposure.others.iter().find(|o| o.who == 101).unwrap().value;
-
+            let exposure = Staking::eras_stakers(1, &11);
             on_offence_now(
                 &[OffenceDetails {
-                    offender: (11, Staking::eras_stakers(0, &11)),
+                    offender: (11, exposure.clone()),
                     reporters: vec![],
                 }],
                 &[Perbill::from_percent(10)],
             );
 
-            assert_eq!(Balances::free_balance(&11), 1000);
-            assert_eq!(Balances::free_balance(&101), 2000);
+            let guaranteed_value = exposure.others.iter().find(|o| o.who == 101).unwrap().value;
+            assert_eq!(Staking::pending_slashes_of(&11), vec![(1, Perbill::from_percent(10) * exposure.own)]);
+            assert_eq!(Staking::pending_slashes_of(&101), vec![(1, Perbill::from_percent(10) * guaranteed_value)]);
+            assert!(Staking::pending_slashes_of(&21).is_empty());
 
-            start_era(2, false);
+            // The slash is applied at the start of era 4
+            start_era(4, false);
+            assert!(Staking::pending_slashes_of(&11).is_empty());
+            assert!(Staking::pending_slashes_of(&101).is_empty());
+        })
+}
 
-            assert_eq!(Balances::free_balance(&11), 1000);
-            assert_eq!(Balances::free_balance(&101), 2000);
+#[test]
+fn reward_history_should_work() {
+    ExtBuilder::default().build().execute_with(|| {
+        <Module<Test>>::reward_by_ids(vec![(11, 1)]);
+        assert!(Staking::reward_history(&11, 0, 10).is_empty());
 
-            start_era(3, false);
+        start_era(1, true);
+        let history_11 = Staking::reward_history(&11, 0, 10);
+        let history_101 = Staking::reward_history(&101, 0, 10);
+        assert_eq!(history_11.len(), 1);
+        assert_eq!(history_11[0].0, 0);
+        assert_eq!(history_101.len(), 1);
+        assert!(Staking::reward_history(&41, 0, 10).is_empty());
+
+        payout_all_stakers(0);
+        let paid_to = |who: AccountId| staking_events().into_iter().fold(0, |acc, event| match event {
+            RawEvent::Reward(stash, amount) if stash == who => acc + amount,
+            _ => acc,
+        });
+        assert_eq!(history_11[0].1, paid_to(11));
+        assert_eq!(history_101[0].1, paid_to(101));
 
-            assert_eq!(Balances::free_balance(&11), 1000);
-            assert_eq!(Balances::free_balance(&101), 2000);
+        // Out of range
+        assert!(Staking::reward_history(&11, 1, 10).is_empty());
+    })
+}
 
-            // at the start of era 4, slashes from era 1 are processed,
-            // after being deferred for at least 2 full eras.
-            start_era(4, false);
+#[test]
+fn accrue_rewards_should_work() {
+    ExtBuilder::default().build().execute_with(|| {
+        // 101 guarantees 11 and 21
+        assert_ok!(Staking::set_accrue_rewards(Origin::signed(100), true));
+        assert!(Staking::accrue_rewards(&101));
+        assert_noop!(Staking::claim_accrued(Origin::signed(100)), Error::<Test>::NoAccruedRewards);
 
-            assert_eq!(Balances::free_balance(&11), 900);
-            assert_eq!(Balances::free_balance(&101), 2000 - (guaranteed_value / 10));
-        })
+        <Module<Test>>::reward_by_ids(vec![(11, 1)]);
+        start_era(1, true);
+        let total_balance_100 = Balances::total_balance(&100);
+        let total_balance_101 = Balances::total_balance(&101);
+        payout_all_stakers(0);
+
+        // Accrued from both validators, nothing paid yet
+        let accrued: Vec<Balance> = staking_events().into_iter().filter_map(|event| match event {
+            RawEvent::RewardsAccrued(101, amount) => Some(amount),
+            _ => None,
+        }).collect();
+        assert_eq!(accrued.len(), 2);
+        let pending = Staking::pending_rewards(&101);
+        assert_eq!(pending, accrued.iter().sum::<Balance>());
+        assert_eq!(pending, Staking::reward_history(&101, 0, 0)[0].1);
+        assert!(!staking_events().iter().any(|event| matches!(event, RawEvent::Reward(101, _))));
+        assert_eq!(Balances::total_balance(&100), total_balance_100);
+        assert_eq!(Balances::total_balance(&101), total_balance_101);
+
+        // Claim once
+        assert_ok!(Staking::claim_accrued(Origin::signed(100)));
+        assert!(staking_events().contains(&RawEvent::RewardsClaimed(101, pending)));
+        assert_eq!(Staking::pending_rewards(&101), 0);
+        assert_eq!(
+            Balances::total_balance(&100) + Balances::total_balance(&101),
+            total_balance_100 + total_balance_101 + pending
+        );
+        assert_noop!(Staking::claim_accrued(Origin::signed(100)), Error::<Test>::NoAccruedRewards);
+
+        // Opt out
+        assert_ok!(Staking::set_accrue_rewards(Origin::signed(100), false));
+        assert!(!Staking::accrue_rewards(&101));
+    })
+}
+
+#[test]
+fn accrued_rewards_are_paid_when_stash_is_killed() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_ok!(Staking::set_accrue_rewards(Origin::signed(100), true));
+        <Module<Test>>::reward_by_ids(vec![(11, 1)]);
+        start_era(1, true);
+        payout_all_stakers(0);
+        let pending = Staking::pending_rewards(&101);
+        assert!(pending > 0);
+        let total_balance_101 = Balances::total_balance(&101);
+
+        // The payee is `Staked`, nothing stays bonded so the stash gets them
+        assert_ok!(Staking::force_unstake(Origin::root(), 101));
+        assert!(staking_events().contains(&RawEvent::RewardsClaimed(101, pending)));
+        assert_eq!(Staking::pending_rewards(&101), 0);
+        assert_eq!(Balances::total_balance(&101), total_balance_101 + pending);
+        assert_eq!(Balances::locks(&101).len(), 0);
+    })
+}
+
+#[test]
+fn guarantee_target_histogram_should_work() {
+    ExtBuilder::default().validator_pool(true).build().execute_with(|| {
+        // 101 guarantees 11 and 21
+        assert_eq!(Staking::guarantee_target_histogram(), vec![(2, 1)]);
+
+        bond_guarantor(200, 1000, vec![(11, 100)]);
+        bond_guarantor(300, 1000, vec![(11, 100), (21, 100), (31, 100)]);
+        bond_guarantor(400, 1000, vec![(41, 100)]);
+        assert_eq!(Staking::guarantee_target_histogram(), vec![(1, 2), (2, 1), (3, 1)]);
+
+        assert_ok!(Staking::chill(Origin::signed(400)));
+        assert_eq!(Staking::guarantee_target_histogram(), vec![(1, 1), (2, 1), (3, 1)]);
+    })
 }
 
 #[test]
@@ -3862,6 +4957,56 @@ fn remove_multi_deferred() {
         })
 }
 
+#[test]
+fn cancel_deferred_slash_for_should_work() {
+    ExtBuilder::default()
+        .slash_defer_duration(2)
+        .build()
+        .execute_with(|| {
+            start_era(1, false);
+            assert!(Staking::deferred_slashes_of_era(1).is_empty());
+
+            let exposure = Staking::eras_stakers(0, &11);
+            on_offence_now(
+                &[OffenceDetails {
+                    offender: (11, exposure.clone()),
+                    reporters: vec![],
+                }],
+                &[Perbill::from_percent(10)],
+            );
+            on_offence_now(
+                &[OffenceDetails {
+                    offender: (21, Staking::eras_stakers(0, &21)),
+                    reporters: vec![],
+                }],
+                &[Perbill::from_percent(10)],
+            );
+            on_offence_now(
+                &[OffenceDetails {
+                    offender: (11, exposure.clone()),
+                    reporters: vec![],
+                }],
+                &[Perbill::from_percent(25)],
+            );
+
+            let slashes = Staking::deferred_slashes_of_era(1);
+            assert_eq!(slashes.iter().map(|s| s.validator).collect::<Vec<_>>(), vec![11, 21, 11]);
+
+            assert_noop!(Staking::cancel_deferred_slash_for(Origin::signed(1), 1, vec![11]), BadOrigin);
+
+            // Both slashes of 11 are cancelled, the non-offender 31 is ignored
+            assert_ok!(Staking::cancel_deferred_slash_for(Origin::root(), 1, vec![11, 31]));
+            let slashes = Staking::deferred_slashes_of_era(1);
+            assert_eq!(slashes.len(), 1);
+            assert_eq!(slashes[0].validator, 21);
+
+            // Only 21 is slashed once applied
+            start_era(4, false);
+            assert_eq!(Balances::free_balance(&11), 1000);
+            assert_eq!(Balances::free_balance(&21), 1900);
+        })
+}
+
 #[test]
 fn update_stakers_should_work_new_era() {
     ExtBuilder::default()
@@ -4320,7 +5465,7 @@ fn multi_guarantees_should_work() {
                 2000
             ));
 
-            // MAX_GUARANTEE should work
+            // MaxGuarantees should work
             for i in 100..115 {
                 <Validators<Test>>::insert(&i, ValidatorPrefs::default());
                 assert_ok!(Staking::guarantee(Origin::signed(4), (i, 10)));
@@ -4337,6 +5482,119 @@ fn multi_guarantees_should_work() {
         });
 }
 
+#[test]
+fn max_guarantees_should_work() {
+    ExtBuilder::default()
+        .guarantee(false)
+        .max_guarantees(2)
+        .validator_pool(true)
+        .build()
+        .execute_with(|| {
+            // Guarantees within the cap are all kept
+            assert_ok!(Staking::guarantee(Origin::signed(100), (11, 100)));
+            assert_ok!(Staking::guarantee(Origin::signed(100), (21, 100)));
+            // Adding votes to an existing target is fine
+            assert_ok!(Staking::guarantee(Origin::signed(100), (21, 100)));
+            assert_eq!(
+                Staking::guarantors(&101).unwrap().targets,
+                vec![
+                    IndividualExposure { who: 11, value: 100 },
+                    IndividualExposure { who: 21, value: 200 },
+                ]
+            );
+
+            // A new target beyond the cap is rejected
+            assert_noop!(
+                Staking::guarantee(Origin::signed(100), (41, 100)),
+                Error::<Test>::ExceedGuaranteeLimit
+            );
+        });
+}
+
+#[test]
+fn min_bond_should_work() {
+    ExtBuilder::default()
+        .guarantee(false)
+        .min_validator_bond(2000)
+        .min_guarantor_bond(600)
+        .build()
+        .execute_with(|| {
+            // 3 bonds below the validator minimum
+            let _ = Balances::make_free_balance_be(&3, 3000);
+            assert_ok!(Staking::bond(Origin::signed(3), 2, 1500));
+            assert_noop!(
+                Staking::validate(Origin::signed(2), ValidatorPrefs::default()),
+                Error::<Test>::InsufficientBond
+            );
+            assert_ok!(Staking::bond_extra(Origin::signed(3), 500));
+            assert_ok!(Staking::validate(Origin::signed(2), ValidatorPrefs::default()));
+            assert!(<Validators<Test>>::contains_key(&3));
+
+            // 101 bonds 500, below the guarantor minimum
+            assert_eq!(Staking::ledger(&100).unwrap().active, 500);
+            assert_noop!(
+                Staking::guarantee(Origin::signed(100), (11, 100)),
+                Error::<Test>::InsufficientBond
+            );
+            assert_ok!(Staking::bond_extra(Origin::signed(101), 100));
+            assert_ok!(Staking::guarantee(Origin::signed(100), (11, 100)));
+            assert_eq!(Staking::guarantors(&101).unwrap().total, 100);
+        });
+}
+
+#[test]
+fn chill_other_should_work() {
+    ExtBuilder::default().build().execute_with(|| {
+        System::set_block_number(1);
+        // 11 still has a stake limit
+        assert_noop!(Staking::chill_other(Origin::signed(1), 11), Error::<Test>::CannotChillOther);
+        // 101 is not a validator
+        assert_noop!(Staking::chill_other(Origin::signed(1), 101), Error::<Test>::CannotChillOther);
+
+        // The stake limit of 11 collapses
+        Staking::upsert_stake_limit(&11, 0);
+        assert_ok!(Staking::chill_other(Origin::signed(1), 11));
+        assert!(!<Validators<Test>>::contains_key(&11));
+        assert_eq!(Staking::stake_limit(&11), None);
+        assert_eq!(*staking_events().last().unwrap(), RawEvent::ChilledOther(1, 11));
+
+        // Can't be chilled twice
+        assert_noop!(Staking::chill_other(Origin::signed(1), 11), Error::<Test>::CannotChillOther);
+    });
+}
+
+#[test]
+fn kick_should_work() {
+    ExtBuilder::default().build().execute_with(|| {
+        System::set_block_number(1);
+        assert_eq!(Staking::guarantors(101).unwrap().targets.len(), 2);
+
+        // Only validators can kick
+        assert_noop!(Staking::kick(Origin::signed(100), vec![101]), Error::<Test>::NotValidator);
+
+        // 11 kicks 101, and the non-guarantor 31 is skipped
+        assert_ok!(Staking::kick(Origin::signed(10), vec![101, 31]));
+        let guarantee = Staking::guarantors(101).unwrap();
+        assert_eq!(guarantee.targets, vec![IndividualExposure { who: 21, value: 250 }]);
+        assert_eq!(guarantee.total, 250);
+        assert_eq!(staking_events(), vec![RawEvent::Kicked(101, 11)]);
+
+        // Kicking again is a no-op
+        assert_ok!(Staking::kick(Origin::signed(10), vec![101]));
+        assert_eq!(Staking::guarantors(101).unwrap(), guarantee);
+        assert_eq!(staking_events().len(), 1);
+
+        // 21 kicks 101, which guarantees nobody now
+        assert_ok!(Staking::kick(Origin::signed(20), vec![101]));
+        assert!(Staking::guarantors(101).is_none());
+
+        // The guarantees are gone from the next era
+        start_era(1, false);
+        assert!(Staking::eras_stakers(1, &11).others.is_empty());
+        assert!(Staking::eras_stakers(1, &21).others.is_empty());
+    });
+}
+
 #[test]
 fn cut_guarantee_should_work() {
     ExtBuilder::default()
@@ -4719,6 +5977,43 @@ fn chill_stash_should_work() {
     });
 }
 
+#[test]
+fn era_payout_status_should_work() {
+    ExtBuilder::default()
+        .guarantee(false)
+        .build()
+        .execute_with(|| {
+            <Module<Test>>::reward_by_ids(vec![(11, 1)]);
+            // 11, 21 and 31 have exposures in era 0
+            assert_eq!(Staking::eras_unclaimed_validators(0), 3);
+            assert_eq!(Staking::era_payout_status(0), EraPayoutStatus::NotComputed);
+
+            // Era 0 just ended
+            start_era(1, true);
+            assert_eq!(Staking::era_payout_status(0), EraPayoutStatus::Claimable);
+            assert_eq!(Staking::era_payout_status(1), EraPayoutStatus::NotComputed);
+
+            assert_ok!(Staking::reward_stakers(Origin::signed(10), 11, 0));
+            assert_eq!(Staking::eras_unclaimed_validators(0), 2);
+            assert_eq!(Staking::era_payout_status(0), EraPayoutStatus::Claimable);
+
+            // 41 has no exposure, it doesn't count
+            payout_all_stakers(0);
+            assert_eq!(Staking::eras_unclaimed_validators(0), 0);
+            assert_eq!(Staking::era_payout_status(0), EraPayoutStatus::FullyClaimed);
+
+            // Era 1 is out of history depth
+            HistoryDepth::put(1);
+            start_era(3, true);
+            assert_eq!(Staking::era_payout_status(1), EraPayoutStatus::Pruned);
+            assert_eq!(Staking::era_payout_status(2), EraPayoutStatus::Claimable);
+
+            // Eras without a counter, e.g. before the upgrade, aren't reported as fully claimed
+            ErasUnclaimedValidators::remove(2);
+            assert_eq!(Staking::era_payout_status(2), EraPayoutStatus::Claimable);
+        })
+}
+
 #[test]
 fn double_claim_rewards_should_fail() {
     ExtBuilder::default()
@@ -4993,6 +6288,105 @@ fn update_stage_two_stake_limit_according_to_mpow_should_work() {
         });
 }
 
+#[test]
+fn stake_limit_of_workload_should_work() {
+    ExtBuilder::default()
+        .guarantee(false)
+        .build()
+        .execute_with(|| {
+            Staking::upsert_stake_limit(&11, 1234);
+            assert_eq!(Staking::stake_limit(&11), Some(1234));
+            assert_eq!(Staking::stake_limit(&1), None);
+
+            // Stage one
+            let mut workload_map = BTreeMap::new();
+            workload_map.insert(11, 3);
+            Staking::report_works(workload_map, 10);
+            assert_eq!(Staking::stake_limit(&11), Some(7500));
+            assert_eq!(Staking::stake_limit_of_workload(3), 7500);
+            assert_eq!(Staking::stake_limit_of_workload(5), Staking::stage_one_stake_limit_of(5));
+
+            // Stage two, the projection matches the stake limit from the report
+            let _ = Balances::make_free_balance_be(&10000, 100_000_000_000_000);
+            let total_issuance = Balances::total_issuance();
+            <ErasTotalStakes<Test>>::insert(0, Perbill::from_percent(20) * total_issuance);
+            let mut workload_map = BTreeMap::new();
+            workload_map.insert(11, 300_000_000_000);
+            Staking::report_works(workload_map, 1_000_000_000_000);
+            assert_eq!(
+                Some(Staking::stake_limit_of_workload(300_000_000_000)),
+                Staking::stake_limit(&11)
+            );
+        });
+}
+
+#[test]
+fn stage_two_stake_limit_should_not_overflow() {
+    ExtBuilder::default().build().execute_with(|| {
+        // Zero total workload
+        assert_eq!(Staking::stage_two_stake_limit_of(100, 0, 1_000_000), 0);
+
+        // Issuance around u64::MAX
+        let total_stake_limit = u64::max_value() as u128 * 10;
+        assert_eq!(
+            Staking::stage_two_stake_limit_of(1 << 40, 1 << 41, total_stake_limit),
+            total_stake_limit / 2
+        );
+
+        // The product overflows u128
+        assert_eq!(
+            Staking::stage_two_stake_limit_of(u128::max_value() / 2, u128::max_value(), u128::max_value() / 4),
+            42535295865117307932921825928971026431
+        );
+        assert_eq!(
+            Staking::stage_two_stake_limit_of(u128::max_value(), 1, u128::max_value()),
+            u128::max_value()
+        );
+
+        // Overflowed workload gives no stake limit in stage one
+        assert_eq!(Staking::stage_one_stake_limit_of(u128::max_value()), 0);
+    })
+}
+
+#[test]
+fn workload_for_stake_should_work() {
+    ExtBuilder::default()
+        .guarantee(false)
+        .build()
+        .execute_with(|| {
+            // No workload reported yet
+            assert_eq!(Staking::workload_for_stake(7500), u128::MAX);
+
+            // Stage one
+            let mut workload_map = BTreeMap::new();
+            workload_map.insert(1, 3);
+            Staking::report_works(workload_map, 10);
+            assert_eq!(Staking::total_workload(), 10);
+            assert_eq!(Staking::workload_for_stake(7500), 3);
+            assert_eq!(Staking::stage_one_stake_limit_of(3), 7500);
+            assert_eq!(Staking::workload_for_stake(7501), 4);
+            assert!(Staking::stage_one_stake_limit_of(4) >= 7501);
+            assert!(Staking::stage_one_stake_limit_of(3) < 7501);
+
+            // Stage two
+            let _ = Balances::make_free_balance_be(&10000, 100_000_000_000_000);
+            let total_issuance = Balances::total_issuance();
+            <ErasTotalStakes<Test>>::insert(0, Perbill::from_percent(20) * total_issuance);
+            let total_workload: u128 = 1_000_000_000_000;
+            let mut workload_map = BTreeMap::new();
+            workload_map.insert(11, 300_000_000_000);
+            Staking::report_works(workload_map, total_workload);
+            let target_stake = Staking::stake_limit(&11).unwrap_or_default();
+            assert!(target_stake > 0);
+
+            let total_stake_limit = Staking::calculate_total_stake_limit();
+            let workload = Staking::workload_for_stake(target_stake);
+            assert!(workload <= 300_000_000_000);
+            assert!(Staking::stage_two_stake_limit_of(workload / 1024, total_workload / 1024, total_stake_limit) >= target_stake);
+            assert!(Staking::stage_two_stake_limit_of(workload / 1024 - 1, total_workload / 1024, total_stake_limit) < target_stake);
+        });
+}
+
 #[test]
 fn check_inverse_function_work() {
     ExtBuilder::default()
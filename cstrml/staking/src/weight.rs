@@ -66,6 +66,11 @@ impl crate::WeightInfo for WeightInfo {
 			.saturating_add(DbWeight::get().reads(3 as Weight))
 			.saturating_add(DbWeight::get().writes(3 as Weight))
 	}
+	fn claim_accrued() -> Weight {
+		(50_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(5 as Weight))
+			.saturating_add(DbWeight::get().writes(4 as Weight))
+	}
 	fn new_era(v: u32, n: u32, m: u32, ) -> Weight {
 		(0 as Weight)
 			.saturating_add((6_597_064_000 as Weight).saturating_mul(v as Weight))
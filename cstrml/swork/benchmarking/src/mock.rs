@@ -114,6 +114,8 @@ impl market::Config for Test {
     type StakingRatio = StakingRatio;
     type StorageRatio = StorageRatio;
     type MaximumFileSize = MaximumFileSize;
+    type OnFileClosed = ();
+    type MinReplication = ();
     type WeightInfo = market::weight::WeightInfo<Test>;
     type RenewRewardRatio = RenewRewardRatio;
 }
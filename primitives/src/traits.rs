@@ -44,6 +44,25 @@ pub trait MarketInterface<AccountId, Balance> {
 	fn update_files_spower(changed_files: &Vec<(MerkleRoot, u64, Vec<(AccountId, AccountId, SworkerAnchor, Option<BlockNumber>)>)>);
 }
 
+/// Handler for the files closed by the market, e.g. to renew or notify in other modules.
+pub trait OnFileClosed {
+	// Called once for each expired file removed from market::FilesV2
+	fn on_file_closed(cid: &MerkleRoot, file_size: u64);
+}
+
+impl OnFileClosed for () {
+	fn on_file_closed(_: &MerkleRoot, _: u64) {}
+}
+
+/// The minimum number of replicas for a file to be durable, by the file size.
+pub trait MinReplication {
+	fn min_replication(file_size: u64) -> u32;
+}
+
+impl MinReplication for () {
+	fn min_replication(_: u64) -> u32 { 1 }
+}
+
 pub trait BenefitInterface<AccountId, Balance, NegativeImbalance> {
 	fn update_era_benefit(next_era: EraIndex, total_benefits: Balance) -> Balance;
 
@@ -5,7 +5,7 @@
 
 use std::sync::Arc;
 
-use crust_runtime::opaque::Block;
+use crust_runtime::{opaque::Block, FileInfoV2};
 use crust_primitives::{BlockNumber, AccountId, Index, Balance, Hash};
 use sp_api::ProvideRuntimeApi;
 use txpool_api::TransactionPool;
@@ -83,6 +83,8 @@ pub fn create_full<C, P, SC, B>(deps: FullDeps<C, P, SC, B>) -> RpcExtension whe
     C::Api: pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>,
     C::Api: BabeApi<Block>,
     C::Api: BlockBuilder<Block>,
+    C::Api: staking_rpc::StakingRuntimeApi<Block, AccountId, Balance>,
+    C::Api: market_rpc::MarketRuntimeApi<Block, FileInfoV2<AccountId, Balance>>,
     P: TransactionPool + Sync + Send + 'static,
     SC: SelectChain<Block> + 'static,
     B: sc_client_api::Backend<Block> + Send + Sync + 'static,
@@ -92,6 +94,8 @@ pub fn create_full<C, P, SC, B>(deps: FullDeps<C, P, SC, B>) -> RpcExtension whe
     use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApi};
     use sc_finality_grandpa_rpc::{GrandpaApi, GrandpaRpcHandler};
     use sc_consensus_babe_rpc::BabeRpcHandler;
+    use staking_rpc::{Staking, StakingApi};
+    use market_rpc::{Market, MarketApi};
 
     let mut io = jsonrpc_core::IoHandler::default();
     let FullDeps {
@@ -121,6 +125,12 @@ pub fn create_full<C, P, SC, B>(deps: FullDeps<C, P, SC, B>) -> RpcExtension whe
     io.extend_with(
         TransactionPaymentApi::to_delegate(TransactionPayment::new(client.clone()))
     );
+    io.extend_with(
+        StakingApi::to_delegate(Staking::new(client.clone()))
+    );
+    io.extend_with(
+        MarketApi::to_delegate(Market::new(client.clone()))
+    );
     io.extend_with(
         sc_consensus_babe_rpc::BabeApi::to_delegate(
             BabeRpcHandler::new(
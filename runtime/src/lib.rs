@@ -66,11 +66,13 @@ use impls::{CurrencyToVoteHandler, Author, OneTenthFee, CurrencyAdapter};
 /// Crust primitives
 use primitives::{
     constants::{time::*, currency::*},
+    traits::MinReplication,
     *
 };
 
 #[cfg(feature = "std")]
 pub use staking::StakerStatus;
+pub use market::FileInfoV2;
 
 /// Opaque types. These are used by the CLI to instantiate machinery that don't need to know
 /// the specifics of the runtime. They can then be made to be agnostic over specific formats
@@ -401,6 +403,26 @@ parameter_types! {
     pub const MarketStakingPotDuration: u32 = 60;
     // free transfer amount for other locks
     pub const UncheckedFrozenBondFund: Balance = 1 * DOLLARS;
+    // Newly bonded stashes are immune in the era they bond and the first era they can be elected in
+    pub const SlashingImmunityEras: EraIndex = 2;
+    // 20 points for a block, 2 for an uncle reference and 1 for an uncle
+    pub const AuthoringPoints: (u32, u32, u32) = (20, 2, 1);
+    // No cap on the total stake of a validator
+    pub const MaxStakePerValidator: Option<Balance> = None;
+    // No self stake requirement for validators
+    pub const MinSelfStakeRatio: Perbill = Perbill::zero();
+    // No limit on the commission changes of validators
+    pub const MaxCommissionIncreasePerEra: Perbill = Perbill::one();
+    // Don't change the validator set if there are not enough candidates
+    pub const Fallback: staking::FallbackMode = staking::FallbackMode::StallElection;
+    // Maximum number of validators a guarantor can guarantee
+    pub const MaxGuarantees: u32 = 16;
+    // Validators can take all the rewards for now
+    pub const MaxCommission: Perbill = Perbill::one();
+    // No minimum bond to validate for now, only the dust check applies
+    pub const MinValidatorBond: Balance = 0;
+    // No minimum bond to guarantee for now, only the dust check applies
+    pub const MinGuarantorBond: Balance = 0;
 }
 
 impl staking::Config for Runtime {
@@ -427,6 +449,16 @@ impl staking::Config for Runtime {
     type MarketStakingPotDuration = MarketStakingPotDuration;
     type BenefitInterface = Benefits;
     type UncheckedFrozenBondFund = UncheckedFrozenBondFund;
+    type SlashingImmunityEras = SlashingImmunityEras;
+    type AuthoringPoints = AuthoringPoints;
+    type MaxStakePerValidator = MaxStakePerValidator;
+    type MinSelfStakeRatio = MinSelfStakeRatio;
+    type MaxCommissionIncreasePerEra = MaxCommissionIncreasePerEra;
+    type Fallback = Fallback;
+    type MaxGuarantees = MaxGuarantees;
+    type MaxCommission = MaxCommission;
+    type MinValidatorBond = MinValidatorBond;
+    type MinGuarantorBond = MinGuarantorBond;
     type WeightInfo = staking::weight::WeightInfo;
 }
 
@@ -770,6 +802,14 @@ parameter_types! {
     pub const RenewRewardRatio: Perbill = Perbill::from_percent(5);
 }
 
+/// Files larger than 1G need all the `FileReplica` replicas to be durable, smaller ones need 2.
+pub struct FileMinReplication;
+impl MinReplication for FileMinReplication {
+    fn min_replication(file_size: u64) -> u32 {
+        if file_size > 1_073_741_824 { FileReplica::get() } else { 2 }
+    }
+}
+
 impl market::Config for Runtime {
     /// The market's module id, used for deriving its sovereign account ID.
     type ModuleId = MarketModuleId;
@@ -788,6 +828,8 @@ impl market::Config for Runtime {
     type StorageDecreaseRatio = StorageDecreaseRatio;
     type StakingRatio = StakingRatio;
     type StorageRatio = StorageRatio;
+    type OnFileClosed = ();
+    type MinReplication = FileMinReplication;
     type WeightInfo = market::weight::WeightInfo<Runtime>;
     type MaximumFileSize = MaximumFileSize;
     type RenewRewardRatio = RenewRewardRatio;
@@ -1128,6 +1170,74 @@ impl_runtime_apis! {
 		}
     }
 
+    impl staking_rpc_runtime_api::StakingApi<Block, AccountId, Balance> for Runtime {
+        fn inflation_info() -> (Perbill, Perbill) {
+            Staking::inflation_info()
+        }
+
+        fn era_payout_status(era: EraIndex) -> staking::EraPayoutStatus {
+            Staking::era_payout_status(era)
+        }
+
+        fn at_risk_stake(guarantor: AccountId) -> Balance {
+            Staking::at_risk_stake(&guarantor)
+        }
+
+        fn reward_history(who: AccountId, from_era: EraIndex, to_era: EraIndex) -> Vec<(EraIndex, Balance)> {
+            Staking::reward_history(&who, from_era, to_era)
+        }
+
+        fn guarantee_target_histogram() -> Vec<(u32, u32)> {
+            Staking::guarantee_target_histogram()
+        }
+
+        fn blocks_authored(era: EraIndex, validator: AccountId) -> u32 {
+            Staking::eras_blocks_authored(era, validator)
+        }
+
+        fn workload_for_stake(target_stake: Balance) -> u128 {
+            Staking::workload_for_stake(target_stake)
+        }
+
+        fn validator_headroom(validator: AccountId) -> Balance {
+            Staking::validator_headroom(&validator)
+        }
+
+        fn active_backing(guarantor: AccountId) -> Vec<(AccountId, Balance)> {
+            Staking::active_backing(&guarantor)
+        }
+
+        fn current_validator_set() -> Vec<staking::ValidatorInfo<AccountId, Balance>> {
+            Staking::current_validator_set()
+        }
+
+        fn validator_risk(validator: AccountId) -> staking::ValidatorRisk {
+            Staking::validator_risk(&validator)
+        }
+
+        fn current_stake_limit(stash: AccountId) -> Option<Balance> {
+            Staking::stake_limit(&stash)
+        }
+
+        fn projected_stake_limit(workload: u128) -> Balance {
+            Staking::stake_limit_of_workload(workload)
+        }
+
+        fn pending_slashes_of(stash: AccountId) -> Vec<(EraIndex, Balance)> {
+            Staking::pending_slashes_of(&stash)
+        }
+
+        fn current_era_points() -> (u32, Vec<(AccountId, u32)>) {
+            Staking::current_era_points()
+        }
+    }
+
+    impl market_rpc_runtime_api::MarketApi<Block, FileInfoV2<AccountId, Balance>> for Runtime {
+        fn files_by_size(min: u64, max: u64, start: u32, limit: u32) -> Vec<(MerkleRoot, FileInfoV2<AccountId, Balance>)> {
+            Market::files_by_size(min, max, start, limit)
+        }
+    }
+
     #[cfg(feature = "runtime-benchmarks")]
     impl frame_benchmarking::Benchmark<Block> for Runtime {
         fn dispatch_benchmark(